@@ -0,0 +1,295 @@
+//! Nothing about Go confines it to the plane:
+//! A cuboid of intersection points,
+//! each adjacent to its neighbours along all three axes,
+//! makes for three-dimensional Go.
+
+use super::{junction, path, Board, Graph};
+use core::util::bool_mat::BoolMat;
+use core::util::indexer;
+use core::util::indexer::Indexer;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// The letters used to name columns, omitting “I” as is customary in Go.
+const COLUMNS: &[u8] = b"ABCDEFGHJKLMNOPQRSTUVWXYZ";
+
+/// The name of a column: “A” to “Z”, continuing with “AA”, “AB”, ….
+fn column_name(mut k: usize) -> String {
+	let base = COLUMNS.len();
+	let mut name = Vec::new();
+	loop {
+		name.push(COLUMNS[k % base]);
+		if k < base {
+			break;
+		}
+		k = k / base - 1;
+	}
+	name.reverse();
+	String::from_utf8(name).unwrap()
+}
+
+/// The column with the given name, if any.
+/// Names too long for a `usize` do not name a column.
+fn column_number(name: &str) -> Option<usize> {
+	let base = COLUMNS.len();
+	let mut k: usize = 0;
+	for c in name.bytes() {
+		let digit = COLUMNS.iter().position(|&l| l == c)?;
+		k = k.checked_mul(base)?.checked_add(digit + 1)?;
+	}
+	k.checked_sub(1)
+}
+
+/// A cuboid of intersection points
+/// in which every point is adjacent to the up to six points
+/// differing by one in a single coordinate.
+/// Points are indexed as `(layer, row, column)`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Cuboid {
+	graph: Graph<indexer::Cuboid>,
+}
+
+impl Cuboid {
+	/// Create a new cuboid with the given number of layers,
+	/// each being a grid of the given measurements.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Cuboid};
+	/// let cuboid = Cuboid::new(3, 4, 5);
+	/// let adj = cuboid.adjacencies();
+	/// assert!(adj[((0, 0, 0), (1, 0, 0))]);
+	/// assert!(adj[((1, 2, 3), (1, 2, 4))]);
+	/// assert!(!adj[((0, 0, 0), (1, 1, 0))]);
+	/// ```
+	pub fn new(depth: usize, height: usize, width: usize) -> Cuboid {
+		let indexer = indexer::Cuboid::new(depth, height, width);
+		// The cuboid is the Cartesian product of three paths.
+		let adj = path(depth)
+			.kronecker_sum(&path(height))
+			.kronecker_sum(&path(width));
+		let adj = adj.reindex(indexer.clone(), indexer);
+		let indexer = PhantomData;
+		let graph = Graph { adj, indexer };
+		Cuboid { graph }
+	}
+
+	fn indexer(&self) -> &indexer::Cuboid {
+		self.graph.adjacencies().rows()
+	}
+
+	/// The name of a point in the usual notation of Go,
+	/// extended by the layer:
+	/// `"D4/2"` is the point in the fourth column
+	/// and the fourth row from the bottom of the second layer.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::Cuboid;
+	/// let cuboid = Cuboid::new(3, 9, 9);
+	/// assert_eq!(cuboid.name((1, 5, 3)), "D4/2");
+	/// assert_eq!(cuboid.name((0, 0, 8)), "J9/1");
+	/// assert_eq!(cuboid.point("D4/2"), Some((1, 5, 3)));
+	/// assert_eq!(cuboid.point("D4/4"), None);
+	/// ```
+	pub fn name(&self, i: (usize, usize, usize)) -> String {
+		let indexer = self.indexer();
+		assert!(indexer.is_valid(i));
+		let (layer, row, column) = i;
+		let row = indexer.height() - row;
+		format!("{}{}/{}", column_name(column), row, layer + 1)
+	}
+
+	/// The point with the given name, see `name`.
+	/// Lower case column names are accepted as well.
+	pub fn point(&self, name: &str) -> Option<(usize, usize, usize)> {
+		let indexer = self.indexer();
+		let name = name.to_ascii_uppercase();
+		let mut parts = name.splitn(2, '/');
+		let point = parts.next()?;
+		let layer: usize = parts.next()?.parse().ok()?;
+		let split = point.find(|c: char| c.is_ascii_digit())?;
+		let (column, row) = point.split_at(split);
+		let column = column_number(column)?;
+		let row: usize = row.parse().ok()?;
+		let i = (
+			layer.checked_sub(1)?,
+			indexer.height().checked_sub(row)?,
+			column,
+		);
+		if row > 0 && indexer.is_valid(i) {
+			Some(i)
+		} else {
+			None
+		}
+	}
+}
+
+impl Board for Cuboid {
+	type I = indexer::Cuboid;
+	type Adj = BoolMat<Self::I, Self::I>;
+
+	fn adjacencies(&self) -> &Self::Adj {
+		self.graph.adjacencies()
+	}
+
+	fn is_hoshi(&self, _i: <Self::I as Indexer>::Index) -> bool {
+		false
+	}
+}
+
+/// Draw the layers from top to bottom,
+/// labelled in the notation used by `name`.
+/// The adjacencies between layers are not drawn:
+/// Every point is adjacent to the points at the same position
+/// in the layers before and after.
+///
+/// ```none
+/// Layer 1
+///   A B C
+/// 2 ┌─┬─┐
+/// 1 └─┴─┘
+///
+/// Layer 2
+///   A B C
+/// 2 ┌─┬─┐
+/// 1 └─┴─┘
+/// ```
+impl fmt::Display for Cuboid {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let indexer = self.indexer();
+		let (height, width) = (indexer.height(), indexer.width());
+		let names: Vec<_> = (0..width).map(column_name).collect();
+		let column = names.iter().map(String::len).max().unwrap_or(1);
+		let row = height.to_string().len();
+		let header: Vec<_> = names
+			.iter()
+			.map(|n| format!("{:<1$}", n, column))
+			.collect();
+		let header = format!("{:1$} {2}", "", row, header.join(" "));
+		for layer in 0..indexer.depth() {
+			if layer > 0 {
+				writeln!(f)?;
+			}
+			writeln!(f, "Layer {}", layer + 1)?;
+			writeln!(f, "{}", header.trim_end())?;
+			for j in 0..height {
+				let line: Vec<_> = (0..width)
+					.map(|k| {
+						junction(
+							j > 0,
+							j + 1 < height,
+							k > 0,
+							k + 1 < width,
+						)
+						.to_string()
+					})
+					.collect();
+				let line = line.join(&"─".repeat(column));
+				writeln!(
+					f,
+					"{:>2$} {}",
+					height - j,
+					line,
+					row
+				)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+// TODO: Throw out as soon as coverage tools can handle doctests.
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::board::degrees;
+	use core::util::bool_vec::BoolVec;
+	use core::util::linear_map::LinearMap;
+	use core::util::sparse_mat::SparseBoolMat;
+
+	#[test]
+	fn doctests() {
+		let cuboid = Cuboid::new(3, 4, 5);
+		let adj = cuboid.adjacencies();
+		assert!(adj[((0, 0, 0), (1, 0, 0))]);
+		assert!(adj[((1, 2, 3), (1, 2, 4))]);
+		assert!(!adj[((0, 0, 0), (1, 1, 0))]);
+
+		let cuboid = Cuboid::new(3, 9, 9);
+		assert_eq!(cuboid.name((1, 5, 3)), "D4/2");
+		assert_eq!(cuboid.name((0, 0, 8)), "J9/1");
+		assert_eq!(cuboid.point("D4/2"), Some((1, 5, 3)));
+		assert_eq!(cuboid.point("D4/4"), None);
+	}
+
+	#[test]
+	fn neighbours() {
+		let cuboid = Cuboid::new(3, 3, 3);
+		let degrees = degrees(cuboid.adjacencies());
+		assert_eq!(degrees[(0, 0, 0)], 3);
+		assert_eq!(degrees[(0, 1, 0)], 4);
+		assert_eq!(degrees[(0, 1, 1)], 5);
+		assert_eq!(degrees[(1, 1, 1)], 6);
+	}
+
+	#[test]
+	fn sparse_agrees() {
+		let cuboid = Cuboid::new(4, 5, 6);
+		let dense = cuboid.adjacencies();
+		let sparse = SparseBoolMat::from_dense(dense);
+		let indexer = dense.rows().clone();
+		// Every point is adjacent to itself,
+		// and every edge is stored in both directions.
+		let edges = 3 * 5 * 6 + 4 * 4 * 6 + 4 * 5 * 5;
+		assert_eq!(sparse.count(), 4 * 5 * 6 + 2 * edges);
+		let mut stones = BoolVec::falses(indexer.clone());
+		for n in (0..indexer.range()).filter(|n| n % 7 < 3) {
+			stones[indexer.to_index(n)] = true;
+		}
+		assert_eq!(sparse.eval(&stones), dense.eval(&stones));
+		assert_eq!(sparse.mul_right(dense), dense * dense);
+		assert_eq!(
+			dense.closure(),
+			BoolMat::trues(indexer.clone(), indexer.clone())
+		);
+		assert_eq!(dense.closure(), dense.pow(3 + 4 + 5));
+		assert!(dense.closure() != dense.pow(3 + 4 + 4));
+	}
+
+	#[test]
+	fn notation() {
+		let cuboid = Cuboid::new(2, 30, 30);
+		let indexer = cuboid.indexer().clone();
+		for n in 0..indexer.range() {
+			let i = indexer.to_index(n);
+			assert_eq!(cuboid.point(&cuboid.name(i)), Some(i));
+		}
+		assert_eq!(cuboid.name((0, 29, 24)), "Z1/1");
+		assert_eq!(cuboid.name((0, 29, 25)), "AA1/1");
+		assert_eq!(cuboid.point("ab30/2"), Some((1, 0, 26)));
+		for name in
+			&["I1/1", "A0/1", "A31/1", "A1/0", "A1/3", "A1", "1/1"]
+		{
+			assert_eq!(cuboid.point(name), None);
+		}
+		let long = format!("{}1/1", "Z".repeat(20));
+		assert_eq!(cuboid.point(&long), None);
+	}
+
+	#[test]
+	fn rendering() {
+		assert_eq!(
+			Cuboid::new(2, 2, 3).to_string(),
+			"Layer 1\n  A B C\n2 ┌─┬─┐\n1 └─┴─┘\n\n\
+			 Layer 2\n  A B C\n2 ┌─┬─┐\n1 └─┴─┘\n",
+		);
+		let wide = Cuboid::new(1, 10, 26).to_string();
+		let lines: Vec<_> = wide.lines().collect();
+		assert!(lines[1].ends_with("Y  Z  AA"));
+		assert!(lines[2].starts_with("10 ┌──┬──┬"));
+		assert!(lines[11].starts_with(" 1 └──┴"));
+	}
+}
@@ -0,0 +1,528 @@
+//! Just enough of GraphViz DOT to read undirected graphs.
+
+use super::{Description, LoadError, MAX_NESTING};
+use std::collections::HashMap;
+
+#[derive(PartialEq, Debug, Clone)]
+enum Token {
+	/// An identifier, a numeral or a quoted string,
+	/// the latter never being a keyword.
+	Id(String, bool),
+	Open,
+	Close,
+	OpenAttributes,
+	CloseAttributes,
+	Semicolon,
+	Comma,
+	Equals,
+	Colon,
+	/// An edge operator, `--` or the directed `->`.
+	Edge(bool),
+}
+
+fn syntax<T>(line: usize, message: &str) -> Result<T, LoadError> {
+	Err(LoadError::Syntax(line, message.to_string()))
+}
+
+/// Splits the input into tokens.
+struct Lexer {
+	chars: Vec<char>,
+	position: usize,
+	line: usize,
+	/// Whether only whitespace precedes the position on its line.
+	line_start: bool,
+}
+
+impl Lexer {
+	fn peek(&self, offset: usize) -> Option<char> {
+		self.chars.get(self.position + offset).cloned()
+	}
+
+	fn next(&mut self) -> Option<char> {
+		let c = self.peek(0);
+		self.position += 1;
+		match c {
+			Some('\n') => {
+				self.line += 1;
+				self.line_start = true;
+			}
+			Some(c) if !c.is_whitespace() => {
+				self.line_start = false
+			}
+			_ => {}
+		}
+		c
+	}
+
+	/// Consume characters as long as they satisfy a predicate.
+	fn take_while<P: Fn(char) -> bool>(&mut self, predicate: P) -> String {
+		let mut taken = String::new();
+		while let Some(c) = self.peek(0).filter(|&c| predicate(c)) {
+			taken.push(c);
+			self.next();
+		}
+		taken
+	}
+
+	/// Skip whitespace and comments,
+	/// returning whether anything is left.
+	fn skip(&mut self) -> Result<bool, LoadError> {
+		loop {
+			match (self.peek(0), self.peek(1)) {
+				(None, _) => return Ok(false),
+				(Some(c), _) if c.is_whitespace() => {
+					self.next();
+				}
+				(Some('#'), _) if self.line_start => {
+					self.take_while(|c| c != '\n');
+				}
+				(Some('/'), Some('/')) => {
+					self.take_while(|c| c != '\n');
+				}
+				(Some('/'), Some('*')) => {
+					let line = self.line;
+					self.position += 2;
+					while (self.peek(0), self.peek(1))
+						!= (Some('*'), Some('/'))
+					{
+						if self.next().is_none() {
+							return syntax(line, "unterminated comment");
+						}
+					}
+					self.position += 2;
+					self.line_start = false;
+				}
+				_ => return Ok(true),
+			}
+		}
+	}
+
+	fn string(&mut self) -> Result<Token, LoadError> {
+		let line = self.line;
+		let mut id = String::new();
+		self.next();
+		loop {
+			match (self.next(), self.peek(0)) {
+				(None, _) => {
+					return syntax(
+						line,
+						"unterminated string",
+					)
+				}
+				(Some('"'), _) => {
+					return Ok(Token::Id(id, true))
+				}
+				(Some('\\'), Some('"')) => {
+					self.next();
+					id.push('"');
+				}
+				(Some('\\'), Some('\n')) => {
+					self.next();
+				}
+				(Some(c), _) => id.push(c),
+			}
+		}
+	}
+
+	fn token(&mut self) -> Result<Token, LoadError> {
+		let word = |c: char| c.is_alphanumeric() || c == '_';
+		let numeral = |c: char| c.is_ascii_digit() || c == '.';
+		let c = self.peek(0).unwrap_or(' ');
+		let token = match (c, self.peek(1)) {
+			('-', Some('-')) => Token::Edge(false),
+			('-', Some('>')) => Token::Edge(true),
+			('"', _) => return self.string(),
+			('<', _) => {
+				return syntax(
+					self.line,
+					"HTML strings are not supported",
+				)
+			}
+			(c, _) if c == '-' || numeral(c) => {
+				self.next();
+				let id = format!(
+					"{}{}",
+					c,
+					self.take_while(numeral)
+				);
+				if id.parse::<f64>().is_err() {
+					return syntax(
+						self.line,
+						"invalid numeral",
+					);
+				}
+				return Ok(Token::Id(id, false));
+			}
+			(c, _) if word(c) => {
+				return Ok(Token::Id(
+					self.take_while(word),
+					false,
+				))
+			}
+			('{', _) => Token::Open,
+			('}', _) => Token::Close,
+			('[', _) => Token::OpenAttributes,
+			(']', _) => Token::CloseAttributes,
+			(';', _) => Token::Semicolon,
+			(',', _) => Token::Comma,
+			('=', _) => Token::Equals,
+			(':', _) => Token::Colon,
+			_ => return syntax(self.line, "unexpected character"),
+		};
+		self.position += match token {
+			Token::Edge(_) => 2,
+			_ => 1,
+		};
+		self.line_start = false;
+		Ok(token)
+	}
+}
+
+/// Split the input into tokens, each with the line it starts on.
+fn tokens(input: &str) -> Result<Vec<(Token, usize)>, LoadError> {
+	let chars = input.chars().collect();
+	let mut lexer = Lexer {
+		chars,
+		position: 0,
+		line: 1,
+		line_start: true,
+	};
+	let mut tokens = Vec::new();
+	while lexer.skip()? {
+		let line = lexer.line;
+		tokens.push((lexer.token()?, line));
+	}
+	Ok(tokens)
+}
+
+struct Parser {
+	tokens: Vec<(Token, usize)>,
+	position: usize,
+	description: Description,
+	/// The index of each vertex in the description.
+	vertices: HashMap<String, usize>,
+	/// How many blocks enclose the position.
+	depth: usize,
+}
+
+impl Parser {
+	fn peek(&self) -> Option<&Token> {
+		self.tokens.get(self.position).map(|(token, _)| token)
+	}
+
+	fn line(&self) -> usize {
+		match self.tokens.get(self.position) {
+			Some(&(_, line)) => line,
+			None => self.tokens.last().map_or(1, |&(_, line)| line),
+		}
+	}
+
+	fn next(&mut self) -> Option<Token> {
+		let token = self.peek().cloned();
+		self.position += 1;
+		token
+	}
+
+	fn error<T>(&self, message: &str) -> Result<T, LoadError> {
+		syntax(self.line(), message)
+	}
+
+	/// Whether the next token is the given keyword.
+	fn keyword(&self, keyword: &str) -> bool {
+		match self.peek() {
+			Some(Token::Id(id, false)) => {
+				id.eq_ignore_ascii_case(keyword)
+			}
+			_ => false,
+		}
+	}
+
+	fn id(&mut self) -> Result<String, LoadError> {
+		match self.next() {
+			Some(Token::Id(id, _)) => Ok(id),
+			_ => {
+				self.position -= 1;
+				self.error("expected an ID")
+			}
+		}
+	}
+
+	fn expect(&mut self, expected: Token) -> Result<(), LoadError> {
+		if self.peek() == Some(&expected) {
+			self.next();
+			Ok(())
+		} else {
+			self.error(&format!("expected {:?}", expected))
+		}
+	}
+
+	fn graph(&mut self) -> Result<(), LoadError> {
+		if self.keyword("strict") {
+			self.next();
+		}
+		if self.keyword("digraph") {
+			return self.error("directed graphs are not supported");
+		}
+		if !self.keyword("graph") {
+			return self.error("expected \"graph\"");
+		}
+		self.next();
+		if let Some(Token::Id(..)) = self.peek() {
+			self.next();
+		}
+		self.block()?;
+		match self.peek() {
+			None => Ok(()),
+			Some(_) => self.error("trailing input"),
+		}
+	}
+
+	/// A statement list in braces,
+	/// refusing to nest them deeper than the stack allows.
+	fn block(&mut self) -> Result<(), LoadError> {
+		if self.depth == MAX_NESTING {
+			return self.error("nested too deeply");
+		}
+		self.depth += 1;
+		let block = self.statements();
+		self.depth -= 1;
+		block
+	}
+
+	fn statements(&mut self) -> Result<(), LoadError> {
+		self.expect(Token::Open)?;
+		loop {
+			match self.peek() {
+				Some(Token::Close) => {
+					self.next();
+					return Ok(());
+				}
+				Some(Token::Semicolon) => {
+					self.next();
+				}
+				Some(_) => self.statement()?,
+				None => return self.error("expected '}'"),
+			}
+		}
+	}
+
+	fn statement(&mut self) -> Result<(), LoadError> {
+		if self.keyword("graph")
+			|| self.keyword("node")
+			|| self.keyword("edge")
+		{
+			self.next();
+			self.attributes()?;
+			return Ok(());
+		}
+		if self.keyword("subgraph") {
+			self.next();
+			if let Some(Token::Id(..)) = self.peek() {
+				self.next();
+			}
+			return self.block();
+		}
+		if self.peek() == Some(&Token::Open) {
+			return self.block();
+		}
+		let mut id = self.id()?;
+		match self.peek() {
+			Some(Token::Equals) => {
+				self.next();
+				self.id()?;
+				return Ok(());
+			}
+			Some(Token::Colon) => {
+				return self.error("ports are not supported")
+			}
+			_ => {}
+		}
+		self.vertex(&id);
+		let mut edge = false;
+		while let Some(&Token::Edge(directed)) = self.peek() {
+			if directed {
+				return self.error(
+					"directed edges are not supported",
+				);
+			}
+			self.next();
+			if self.keyword("subgraph")
+				|| self.peek() == Some(&Token::Open)
+			{
+				return self.error(
+					"edges to subgraphs are not supported",
+				);
+			}
+			let other = self.id()?;
+			self.vertex(&other);
+			self.description.edges.push((id, other.clone()));
+			id = other;
+			edge = true;
+		}
+		let attributes = self.attributes()?;
+		if !edge {
+			if let Some(position) = attributes.get("pos") {
+				let position = self.position(position)?;
+				let n = self.vertices[&id];
+				self.description.vertices[n].1 = Some(position);
+			}
+		}
+		Ok(())
+	}
+
+	/// Make sure a vertex is known.
+	fn vertex(&mut self, id: &str) {
+		if !self.vertices.contains_key(id) {
+			let n = self.description.vertices.len();
+			self.vertices.insert(id.to_string(), n);
+			self.description.vertices.push((id.to_string(), None));
+		}
+	}
+
+	/// Parse the coordinates in a `pos` attribute.
+	fn position(&self, position: &str) -> Result<Vec<f64>, LoadError> {
+		let position = position.trim_end_matches('!');
+		let coordinates: Option<Vec<f64>> = position
+			.split(',')
+			.map(|x| {
+				x.trim().parse()
+					.ok()
+					.filter(|x: &f64| x.is_finite())
+			})
+			.collect();
+		match coordinates {
+			Some(ref c) if c.len() == 2 || c.len() == 3 => {
+				Ok(coordinates.unwrap())
+			}
+			_ => self.error("invalid position"),
+		}
+	}
+
+	/// Any number of attribute lists.
+	fn attributes(&mut self) -> Result<HashMap<String, String>, LoadError> {
+		let mut attributes = HashMap::new();
+		while self.peek() == Some(&Token::OpenAttributes) {
+			self.next();
+			loop {
+				match self.peek() {
+					Some(Token::CloseAttributes) => {
+						self.next();
+						break;
+					}
+					Some(Token::Semicolon)
+					| Some(Token::Comma) => {
+						self.next();
+					}
+					_ => {
+						let key = self.id()?;
+						self.expect(Token::Equals)?;
+						let value = self.id()?;
+						attributes.insert(key, value);
+					}
+				}
+			}
+		}
+		Ok(attributes)
+	}
+}
+
+/// Read an undirected graph.
+pub(super) fn parse(input: &str) -> Result<Description, LoadError> {
+	let mut parser = Parser {
+		tokens: tokens(input)?,
+		position: 0,
+		description: Description::default(),
+		vertices: HashMap::new(),
+		depth: 0,
+	};
+	parser.graph()?;
+	Ok(parser.description)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::Custom;
+	use super::*;
+	use core::board::Board;
+
+	#[test]
+	fn lexer() {
+		assert_eq!(
+			tokens("a -- \"b \\\"c\\\"\"\n/* x\ny */ -1.5;")
+				.unwrap(),
+			vec![
+				(Token::Id("a".to_string(), false), 1),
+				(Token::Edge(false), 1),
+				(Token::Id("b \"c\"".to_string(), true), 1),
+				(Token::Id("-1.5".to_string(), false), 3),
+				(Token::Semicolon, 3),
+			]
+		);
+		assert!(tokens("a <b>").is_err());
+		assert!(tokens("/* a").is_err());
+		assert!(tokens("\"a").is_err());
+	}
+
+	#[test]
+	fn import() {
+		let board = Custom::from_dot(
+			"# generated\n\
+			 strict graph G {\n\
+			 \tgraph [splines=true]; node [shape=circle]\n\
+			 \trankdir = LR\n\
+			 \ta -- b -- c [color=red]; // a path\n\
+			 \tsubgraph cluster { \"the end\" [pos=\"1,2,3!\"] }\n\
+			 \tc -- \"the end\"\n\
+			 \t1 [label = \"one\"]\n\
+			 }",
+		)
+		.unwrap();
+		let v = |name| board.vertex(name).unwrap();
+		let adj = board.adjacencies();
+		assert!(adj[(v("a"), v("b"))]);
+		assert!(adj[(v("b"), v("c"))]);
+		assert!(!adj[(v("a"), v("c"))]);
+		assert!(adj[(v("the end"), v("c"))]);
+		assert!(!adj[(v("1"), v("a"))]);
+		assert_eq!(
+			board.position(v("the end")),
+			Some(&[1.0, 2.0, 3.0][..])
+		);
+		assert_eq!(board.position(v("a")), None);
+	}
+
+	#[test]
+	fn unsupported() {
+		let error = |input| match Custom::from_dot(input).unwrap_err() {
+			LoadError::Syntax(line, message) => (line, message),
+			error => panic!("unexpected {:?}", error),
+		};
+		assert_eq!(
+			error("digraph { a -> b }").1,
+			"directed graphs are not supported"
+		);
+		assert_eq!(
+			error("graph {\na -> b }"),
+			(2, "directed edges are not supported".to_string())
+		);
+		assert_eq!(
+			error("graph { a:n -- b }").1,
+			"ports are not supported"
+		);
+		assert_eq!(
+			error("graph { a -- { b c } }").1,
+			"edges to subgraphs are not supported"
+		);
+		assert_eq!(
+			error("graph { a [pos=\"1\"] }").1,
+			"invalid position"
+		);
+		assert_eq!(error("graph { a } b").1, "trailing input");
+		assert_eq!(error("graph { a ").1, "expected '}'");
+		assert_eq!(error("{ a }").1, "expected \"graph\"");
+		// Deep nesting is refused instead of overflowing the stack.
+		let deep = format!("graph {}", "{".repeat(100_000));
+		assert_eq!(error(&deep).1, "nested too deeply");
+		assert!(Custom::from_dot("graph { { { a } } }").is_ok());
+	}
+}
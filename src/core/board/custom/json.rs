@@ -0,0 +1,445 @@
+//! Just enough of JSON to read adjacency lists.
+
+use super::{Description, LoadError, MAX_NESTING};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A parsed JSON value.
+/// Objects keep their entries in order.
+#[derive(PartialEq, Debug)]
+enum Value {
+	Null,
+	Bool(bool),
+	Number(f64),
+	String(String),
+	Array(Vec<Located>),
+	Object(Vec<(String, Located)>),
+}
+
+/// A value together with the line it starts on,
+/// to point errors in the structure to the right place.
+#[derive(PartialEq, Debug)]
+struct Located {
+	line: usize,
+	value: Value,
+}
+
+struct Parser<'a> {
+	chars: Peekable<Chars<'a>>,
+	line: usize,
+	/// How many arrays and objects enclose the position.
+	depth: usize,
+}
+
+impl<'a> Parser<'a> {
+	fn error<T>(&self, message: &str) -> Result<T, LoadError> {
+		Err(LoadError::Syntax(self.line, message.to_string()))
+	}
+
+	fn next(&mut self) -> Option<char> {
+		let c = self.chars.next();
+		if c == Some('\n') {
+			self.line += 1;
+		}
+		c
+	}
+
+	/// Skip whitespace and look at the next character.
+	fn peek(&mut self) -> Option<char> {
+		while let Some(&c) = self.chars.peek() {
+			if !c.is_whitespace() {
+				return Some(c);
+			}
+			self.next();
+		}
+		None
+	}
+
+	fn expect(&mut self, expected: char) -> Result<(), LoadError> {
+		if self.peek() == Some(expected) {
+			self.next();
+			Ok(())
+		} else {
+			self.error(&format!("expected {:?}", expected))
+		}
+	}
+
+	fn value(&mut self) -> Result<Located, LoadError> {
+		let next = self.peek();
+		let line = self.line;
+		let value = match next {
+			Some('{') => self.nested(Self::object),
+			Some('[') => self.nested(Self::array),
+			Some('"') => self.string().map(Value::String),
+			Some(c) if c == '-' || c.is_ascii_digit() => {
+				self.number()
+			}
+			Some(c) if c.is_ascii_alphabetic() => self.keyword(),
+			_ => self.error("expected a value"),
+		}?;
+		Ok(Located { line, value })
+	}
+
+	/// Parse an array or object,
+	/// refusing to nest them deeper than the stack allows.
+	fn nested<F>(&mut self, parse: F) -> Result<Value, LoadError>
+	where
+		F: FnOnce(&mut Self) -> Result<Value, LoadError>,
+	{
+		if self.depth == MAX_NESTING {
+			return self.error("nested too deeply");
+		}
+		self.depth += 1;
+		let value = parse(self);
+		self.depth -= 1;
+		value
+	}
+
+	/// Parse a comma-separated sequence up to the closing delimiter.
+	fn sequence<T, F>(
+		&mut self,
+		close: char,
+		mut item: F,
+	) -> Result<Vec<T>, LoadError>
+	where
+		F: FnMut(&mut Self) -> Result<T, LoadError>,
+	{
+		self.next();
+		let mut items = Vec::new();
+		if self.peek() == Some(close) {
+			self.next();
+			return Ok(items);
+		}
+		loop {
+			items.push(item(self)?);
+			match self.peek() {
+				Some(',') => {
+					self.next();
+				}
+				Some(c) if c == close => {
+					self.next();
+					return Ok(items);
+				}
+				_ => {
+					return self.error(&format!(
+						"expected ',' or {:?}",
+						close
+					))
+				}
+			}
+		}
+	}
+
+	fn object(&mut self) -> Result<Value, LoadError> {
+		let entries = self.sequence('}', |parser| {
+			if parser.peek() != Some('"') {
+				return parser.error("expected a key");
+			}
+			let key = parser.string()?;
+			parser.expect(':')?;
+			Ok((key, parser.value()?))
+		})?;
+		Ok(Value::Object(entries))
+	}
+
+	fn array(&mut self) -> Result<Value, LoadError> {
+		self.sequence(']', Self::value).map(Value::Array)
+	}
+
+	fn string(&mut self) -> Result<String, LoadError> {
+		self.next();
+		let mut string = String::new();
+		loop {
+			match self.next() {
+				Some('"') => return Ok(string),
+				Some('\\') => {
+					let c = match self.next() {
+						Some('"') => '"',
+						Some('\\') => '\\',
+						Some('/') => '/',
+						Some('b') => '\u{8}',
+						Some('f') => '\u{c}',
+						Some('n') => '\n',
+						Some('r') => '\r',
+						Some('t') => '\t',
+						Some('u') => self.unicode()?,
+						_ => return self.error(
+							"invalid escape",
+						),
+					};
+					string.push(c);
+				}
+				Some(c) if c < ' ' => {
+					return self.error(
+						"control character in string",
+					)
+				}
+				Some(c) => string.push(c),
+				None => {
+					return self
+						.error("unterminated string")
+				}
+			}
+		}
+	}
+
+	/// Read the four hexadecimal digits after `\u`,
+	/// and a second escape if they form half a surrogate pair.
+	fn unicode(&mut self) -> Result<char, LoadError> {
+		let hex = |parser: &mut Self| {
+			let digits: String =
+				(0..4).filter_map(|_| parser.next()).collect();
+			match u32::from_str_radix(&digits, 16) {
+				Ok(n) if digits.len() == 4 => Ok(n),
+				_ => parser.error("invalid unicode escape"),
+			}
+		};
+		let high = hex(self)?;
+		let code = if (0xd800..0xdc00).contains(&high) {
+			if self.next() != Some('\\') || self.next() != Some('u')
+			{
+				return self.error("unpaired surrogate");
+			}
+			let low = hex(self)?;
+			if !(0xdc00..0xe000).contains(&low) {
+				return self.error("unpaired surrogate");
+			}
+			0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+		} else {
+			high
+		};
+		match ::std::char::from_u32(code) {
+			Some(c) => Ok(c),
+			None => self.error("unpaired surrogate"),
+		}
+	}
+
+	fn number(&mut self) -> Result<Value, LoadError> {
+		let mut number = String::new();
+		while let Some(&c) = self.chars.peek() {
+			if !(c.is_ascii_digit() || "+-.eE".contains(c)) {
+				break;
+			}
+			number.push(c);
+			self.next();
+		}
+		match number.parse() {
+			Ok(x) => Ok(Value::Number(x)),
+			Err(_) => self.error("invalid number"),
+		}
+	}
+
+	fn keyword(&mut self) -> Result<Value, LoadError> {
+		let mut word = String::new();
+		while let Some(&c) = self.chars.peek() {
+			if !c.is_ascii_alphabetic() {
+				break;
+			}
+			word.push(c);
+			self.next();
+		}
+		match word.as_str() {
+			"null" => Ok(Value::Null),
+			"true" => Ok(Value::Bool(true)),
+			"false" => Ok(Value::Bool(false)),
+			_ => self.error("expected a value"),
+		}
+	}
+}
+
+/// Parse a complete JSON document.
+fn document(input: &str) -> Result<Located, LoadError> {
+	let chars = input.chars().peekable();
+	let mut parser = Parser {
+		chars,
+		line: 1,
+		depth: 0,
+	};
+	let value = parser.value()?;
+	match parser.peek() {
+		None => Ok(value),
+		Some(_) => parser.error("trailing characters"),
+	}
+}
+
+fn structure<T>(line: usize, message: &str) -> Result<T, LoadError> {
+	Err(LoadError::Syntax(line, message.to_string()))
+}
+
+fn list(located: Located) -> Result<Vec<Located>, LoadError> {
+	match located.value {
+		Value::Array(items) => Ok(items),
+		_ => structure(located.line, "expected a list"),
+	}
+}
+
+/// A neighbour given by its name.
+fn name(located: Located) -> Result<String, LoadError> {
+	match located.value {
+		Value::String(name) => Ok(name),
+		_ => structure(located.line, "expected a name"),
+	}
+}
+
+/// A neighbour given by its index, which also serves as its name.
+fn index(located: Located) -> Result<String, LoadError> {
+	match located.value {
+		Value::Number(k) if k >= 0.0 && k.fract() == 0.0 => {
+			Ok((k as usize).to_string())
+		}
+		_ => structure(located.line, "expected an index"),
+	}
+}
+
+/// Read adjacency lists,
+/// either as an object of lists of names
+/// or as an array of lists of indices.
+pub(super) fn parse(input: &str) -> Result<Description, LoadError> {
+	let mut description = Description::default();
+	let document = document(input)?;
+	match document.value {
+		Value::Object(entries) => {
+			for (vertex, neighbours) in entries {
+				let neighbours = list(neighbours)?
+					.into_iter()
+					.map(name)
+					.collect::<Result<_, _>>()?;
+				description
+					.vertices
+					.push((vertex.clone(), None));
+				description
+					.adjacency_lists
+					.push((vertex, neighbours));
+			}
+		}
+		Value::Array(lists) => {
+			for (n, neighbours) in lists.into_iter().enumerate() {
+				let neighbours = list(neighbours)?
+					.into_iter()
+					.map(index)
+					.collect::<Result<_, _>>()?;
+				let vertex = n.to_string();
+				description
+					.vertices
+					.push((vertex.clone(), None));
+				description
+					.adjacency_lists
+					.push((vertex, neighbours));
+			}
+		}
+		_ => {
+			return structure(
+				document.line,
+				"expected an object or an array",
+			)
+		}
+	}
+	Ok(description)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::Custom;
+	use super::*;
+	use core::board::{Board, GraphError};
+
+	/// A value on the given line.
+	fn at(line: usize, value: Value) -> Located {
+		Located { line, value }
+	}
+
+	#[test]
+	fn values() {
+		assert_eq!(
+			document(
+				" {\"a\": [1, -2.5e1,\ntrue, null], \"\\u00e9\\n\": {}}"
+			)
+			.unwrap(),
+			at(
+				1,
+				Value::Object(vec![
+					(
+						"a".to_string(),
+						at(
+							1,
+							Value::Array(vec![
+								at(1, Value::Number(1.0)),
+								at(1, Value::Number(-25.0)),
+								at(2, Value::Bool(true)),
+								at(2, Value::Null),
+							])
+						)
+					),
+					("é\n".to_string(), at(2, Value::Object(vec![]))),
+				])
+			)
+		);
+		assert_eq!(
+			document("\"\\ud83d\\ude00\"").unwrap().value,
+			Value::String("😀".to_string())
+		);
+		assert_eq!(
+			document("[1,\n2,\n]").unwrap_err(),
+			LoadError::Syntax(3, "expected a value".to_string())
+		);
+		assert!(document("[1] 2").is_err());
+		assert!(document("{\"a\" 1}").is_err());
+		assert!(document("\"abc").is_err());
+		assert!(document("nil").is_err());
+		// Deep nesting is refused instead of overflowing the stack.
+		let deep = "[".repeat(100_000);
+		assert_eq!(
+			document(&deep).unwrap_err(),
+			LoadError::Syntax(1, "nested too deeply".to_string())
+		);
+		assert!(document("[[[[{\"a\": []}]]]]").is_ok());
+	}
+
+	#[test]
+	fn adjacency_lists() {
+		let board = Custom::from_json(
+			r#"{"a": ["b", "c"], "b": ["a"], "c": ["a"]}"#,
+		)
+		.unwrap();
+		let v = |name| board.vertex(name).unwrap();
+		let adj = board.adjacencies();
+		assert!(adj[(v("a"), v("b"))]);
+		assert!(adj[(v("c"), v("c"))]);
+		assert!(!adj[(v("b"), v("c"))]);
+
+		let board =
+			Custom::from_json("[[1, 2], [0], [0], []]").unwrap();
+		let v = |name| board.vertex(name).unwrap();
+		assert!(board.adjacencies()[(v("2"), v("0"))]);
+		assert!(!board.adjacencies()[(v("3"), v("0"))]);
+	}
+
+	#[test]
+	fn invalid_lists() {
+		assert_eq!(
+			Custom::from_json(r#"{"a": ["b"], "b": []}"#)
+				.unwrap_err(),
+			LoadError::Graph(GraphError::Asymmetric(
+				"a".to_string(),
+				"b".to_string()
+			))
+		);
+		assert_eq!(
+			Custom::from_json("[[1]]").unwrap_err(),
+			LoadError::UnknownVertex("1".to_string())
+		);
+		assert!(Custom::from_json("[[0.5]]").is_err());
+		assert!(Custom::from_json(r#"{"a": "b"}"#).is_err());
+		assert!(Custom::from_json("3").is_err());
+		// Errors in the structure point to the offending value.
+		assert_eq!(
+			Custom::from_json("{\"a\": [\"b\"],\n\"b\": [1]}")
+				.unwrap_err(),
+			LoadError::Syntax(2, "expected a name".to_string())
+		);
+		assert_eq!(
+			Custom::from_json("[\n[1],\n0]").unwrap_err(),
+			LoadError::Syntax(3, "expected a list".to_string())
+		);
+	}
+}
@@ -0,0 +1,610 @@
+//! Boards given as data instead of code.
+//! A `Custom` board is a graph on named vertices,
+//! optionally with a layout for drawing it,
+//! star points and an order for placing handicap stones.
+//!
+//! Boards are read from and written to a simple line-based format.
+//! Everything after a `#` is a comment,
+//! names containing whitespace, `#` or `"` are written in double quotes
+//! with `\"` and `\\` as escapes.
+//!
+//! ```none
+//! # A triangle with a tail.
+//! vertex a 0 0
+//! vertex b 2 0
+//! vertex c 1 1.5
+//! vertex "the tail" 1 3
+//! edge a b
+//! edge b c
+//! edge c a
+//! edge c "the tail"
+//! star c
+//! handicap c a b
+//! ```
+//!
+//! Every vertex is declared by a `vertex` line,
+//! optionally followed by two or three coordinates,
+//! which have to be the same number for all vertices.
+//! Edges are undirected.
+//! Star points and the handicap order
+//! may be spread over several lines.
+//!
+//! Graphs may also be imported from GraphViz DOT
+//! and from adjacency lists in JSON.
+
+use super::{Board, Graph, GraphError};
+use core::util::bool_mat::BoolMat;
+use core::util::indexer::{Handle, Indexer, Labelled};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+mod dot;
+mod json;
+
+/// How deeply brackets may nest in imported graphs.
+/// The parsers descend recursively,
+/// so this keeps malicious input from overflowing the stack.
+const MAX_NESTING: usize = 128;
+
+/// A board read from a description.
+#[derive(PartialEq, Debug)]
+pub struct Custom {
+	graph: Graph<Labelled<String>>,
+	positions: Vec<Option<Vec<f64>>>,
+	star_points: Vec<Handle>,
+	handicap: Vec<Handle>,
+}
+
+/// The coordinates of the layout are finite,
+/// so equality is reflexive after all.
+impl Eq for Custom {}
+
+impl Custom {
+	/// Import a board from an undirected graph in GraphViz DOT.
+	/// The node IDs become the names of the vertices
+	/// and a `pos` attribute of a node is used for the layout.
+	/// Subgraphs are flattened, ports and HTML strings are not supported.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Custom};
+	/// let board = Custom::from_dot(
+	///	"graph { a -- b -- c; c -- a; d [pos=\"1,2\"]; a -- d }",
+	/// )
+	/// .unwrap();
+	/// let (a, d) = (board.vertex("a").unwrap(), board.vertex("d").unwrap());
+	/// assert!(board.adjacencies()[(a, d)]);
+	/// assert_eq!(board.position(d), Some(&[1.0, 2.0][..]));
+	/// ```
+	pub fn from_dot(input: &str) -> Result<Custom, LoadError> {
+		dot::parse(input)?.build()
+	}
+
+	/// Import a board from adjacency lists in JSON.
+	/// These are either given as an object
+	/// mapping the name of each vertex to a list of the names
+	/// of its neighbours,
+	/// or as an array of lists of the indices of the neighbours,
+	/// in which case the vertices are named by their index.
+	/// The lists have to be symmetric.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Custom};
+	/// let path = Custom::from_json("[[1], [0, 2], [1]]").unwrap();
+	/// let (a, c) = (path.vertex("0").unwrap(), path.vertex("2").unwrap());
+	/// assert!(!path.adjacencies()[(a, c)]);
+	/// let pair = Custom::from_json(r#"{"a": ["b"], "b": ["a"]}"#).unwrap();
+	/// assert_eq!(pair.to_string(), "vertex a\nvertex b\nedge a b\n");
+	/// ```
+	pub fn from_json(input: &str) -> Result<Custom, LoadError> {
+		json::parse(input)?.build()
+	}
+
+	fn indexer(&self) -> &Labelled<String> {
+		self.graph.adjacencies().rows()
+	}
+
+	/// The vertex of the given name, if there is one.
+	pub fn vertex(&self, name: &str) -> Option<Handle> {
+		self.indexer().handle(name)
+	}
+
+	/// The name of a vertex.
+	pub fn name(&self, vertex: Handle) -> &str {
+		self.indexer().label(vertex)
+	}
+
+	/// The coordinates of a vertex in the layout, if it has any.
+	pub fn position(&self, vertex: Handle) -> Option<&[f64]> {
+		let n = self.indexer().to_num(vertex);
+		self.positions[n].as_deref()
+	}
+
+	/// The star points, in the order they were given in.
+	pub fn star_points(&self) -> &[Handle] {
+		&self.star_points
+	}
+
+	/// The vertices to place handicap stones on,
+	/// in the order they are to be used.
+	pub fn handicap(&self) -> &[Handle] {
+		&self.handicap
+	}
+}
+
+impl Board for Custom {
+	type I = Labelled<String>;
+	type Adj = BoolMat<Self::I, Self::I>;
+
+	fn adjacencies(&self) -> &Self::Adj {
+		self.graph.adjacencies()
+	}
+
+	fn is_hoshi(&self, i: Handle) -> bool {
+		self.star_points.contains(&i)
+	}
+}
+
+/// The reasons a description might not make for a valid board.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum LoadError {
+	/// The given line, counting from one, could not be understood.
+	Syntax(usize, String),
+	/// The vertex is declared more than once.
+	DuplicateVertex(String),
+	/// The vertex is used without being declared.
+	UnknownVertex(String),
+	/// The vertex has a different number of coordinates
+	/// than the ones before.
+	Layout(String),
+	/// The vertex is given as star point more than once.
+	DuplicateStarPoint(String),
+	/// The vertex appears in the handicap order more than once.
+	DuplicateHandicap(String),
+	/// The edges do not make for a valid graph.
+	Graph(GraphError<String>),
+}
+
+impl fmt::Display for LoadError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			LoadError::Syntax(line, message) => {
+				write!(f, "line {}: {}", line, message)
+			}
+			LoadError::DuplicateVertex(name) => {
+				write!(f, "vertex {:?} declared twice", name)
+			}
+			LoadError::UnknownVertex(name) => {
+				write!(f, "unknown vertex {:?}", name)
+			}
+			LoadError::Layout(name) => {
+				write!(
+					f,
+					"vertex {:?} has the wrong dimension",
+					name
+				)
+			}
+			LoadError::DuplicateStarPoint(name) => {
+				write!(f, "star point {:?} given twice", name)
+			}
+			LoadError::DuplicateHandicap(name) => {
+				write!(
+					f,
+					"handicap point {:?} given twice",
+					name
+				)
+			}
+			LoadError::Graph(error) => error.fmt(f),
+		}
+	}
+}
+
+impl Error for LoadError {}
+
+/// The contents of a description, before checking them.
+#[derive(Default, Debug)]
+struct Description {
+	vertices: Vec<(String, Option<Vec<f64>>)>,
+	edges: Vec<(String, String)>,
+	adjacency_lists: Vec<(String, Vec<String>)>,
+	star_points: Vec<String>,
+	handicap: Vec<String>,
+}
+
+impl Description {
+	/// Check everything and build the board.
+	fn build(self) -> Result<Custom, LoadError> {
+		if let Some(name) = repeated(&self.star_points) {
+			return Err(LoadError::DuplicateStarPoint(
+				name.clone(),
+			));
+		}
+		if let Some(name) = repeated(&self.handicap) {
+			return Err(LoadError::DuplicateHandicap(name.clone()));
+		}
+		let mut seen = HashSet::new();
+		let mut dimension = None;
+		for (name, position) in &self.vertices {
+			if !seen.insert(name) {
+				return Err(LoadError::DuplicateVertex(
+					name.clone(),
+				));
+			}
+			if let Some(position) = position {
+				if *dimension.get_or_insert(position.len())
+					!= position.len()
+				{
+					return Err(LoadError::Layout(
+						name.clone(),
+					));
+				}
+			}
+		}
+		let (names, positions): (Vec<_>, Vec<_>) =
+			self.vertices.into_iter().unzip();
+		let indexer = Labelled::new(names);
+		let handle = |name: &String| {
+			indexer.handle(name.as_str()).ok_or_else(|| {
+				LoadError::UnknownVertex(name.clone())
+			})
+		};
+		let handles = |names: &[String]| -> Result<Vec<_>, _> {
+			names.iter().map(handle).collect()
+		};
+		let mut builder = Graph::builder(indexer.clone());
+		for (a, b) in &self.edges {
+			builder = builder.edge(handle(a)?, handle(b)?);
+		}
+		for (vertex, neighbours) in &self.adjacency_lists {
+			let neighbours = handles(neighbours)?;
+			builder = builder
+				.adjacency_list(handle(vertex)?, neighbours);
+		}
+		let name = |h: Handle| indexer.label(h).clone();
+		let graph = builder.build().map_err(|error| {
+			LoadError::Graph(match error {
+				GraphError::InvalidVertex(i) => {
+					GraphError::InvalidVertex(name(i))
+				}
+				GraphError::Asymmetric(a, b) => {
+					GraphError::Asymmetric(name(a), name(b))
+				}
+				GraphError::Irreflexive(i) => {
+					GraphError::Irreflexive(name(i))
+				}
+				GraphError::NonSquare => GraphError::NonSquare,
+			})
+		})?;
+		Ok(Custom {
+			graph,
+			positions,
+			star_points: handles(&self.star_points)?,
+			handicap: handles(&self.handicap)?,
+		})
+	}
+}
+
+/// Find a name which is given more than once.
+fn repeated(names: &[String]) -> Option<&String> {
+	let mut seen = HashSet::new();
+	names.iter().find(|&name| !seen.insert(name))
+}
+
+/// Split a line into words,
+/// respecting quotes and dropping comments.
+fn words(line: &str) -> Result<Vec<String>, String> {
+	let mut words = Vec::new();
+	let mut chars = line.chars().peekable();
+	while let Some(&c) = chars.peek() {
+		if c.is_whitespace() {
+			chars.next();
+		} else if c == '#' {
+			break;
+		} else if c == '"' {
+			chars.next();
+			let mut word = String::new();
+			loop {
+				match chars.next() {
+					Some('"') => break,
+					Some('\\') => match chars.next() {
+						Some(c @ '"')
+						| Some(c @ '\\') => word.push(c),
+						_ => return Err(
+							"invalid escape"
+								.to_string(),
+						),
+					},
+					Some(c) => word.push(c),
+					None => {
+						return Err(
+							"unterminated quote"
+								.to_string(),
+						)
+					}
+				}
+			}
+			words.push(word);
+		} else {
+			let mut word = String::new();
+			while let Some(&c) = chars.peek() {
+				if c.is_whitespace() || c == '#' || c == '"' {
+					break;
+				}
+				word.push(c);
+				chars.next();
+			}
+			words.push(word);
+		}
+	}
+	Ok(words)
+}
+
+/// Read the coordinates of a vertex, if it has any.
+fn coordinates(words: &[String]) -> Result<Option<Vec<f64>>, &'static str> {
+	let coordinate = |word: &String| {
+		word.parse().ok().filter(|x: &f64| x.is_finite())
+	};
+	match words.len() {
+		0 => Ok(None),
+		2 | 3 => words
+			.iter()
+			.map(coordinate)
+			.collect::<Option<Vec<_>>>()
+			.map(Some)
+			.ok_or("invalid coordinate"),
+		_ => Err("expected 2 or 3 coordinates"),
+	}
+}
+
+/// Write a name such that `words` reads it back as a single word.
+fn quote(name: &str) -> String {
+	let plain = |c: char| !(c.is_whitespace() || c == '#' || c == '"');
+	if !name.is_empty() && name.chars().all(plain) {
+		name.to_string()
+	} else {
+		let escaped = name.replace('\\', "\\\\").replace('"', "\\\"");
+		format!("\"{}\"", escaped)
+	}
+}
+
+impl FromStr for Custom {
+	type Err = LoadError;
+
+	/// Read a board in the format described in the module.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Custom};
+	/// let input = "vertex a 0 0\nvertex b 1 0\nedge a b\nstar a\n";
+	/// let board: Custom = input.parse().unwrap();
+	/// let (a, b) = (board.vertex("a").unwrap(), board.vertex("b").unwrap());
+	/// assert!(board.adjacencies()[(a, b)]);
+	/// assert!(board.is_hoshi(a));
+	/// assert_eq!(board.to_string(), input);
+	/// ```
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		let mut description = Description::default();
+		for (number, line) in input.lines().enumerate() {
+			let syntax = |message: &str| {
+				LoadError::Syntax(
+					number + 1,
+					message.to_string(),
+				)
+			};
+			let words = words(line).map_err(|m| syntax(&m))?;
+			let (keyword, arguments) = match words.split_first() {
+				Some(split) => split,
+				None => continue,
+			};
+			match keyword.as_str() {
+				"vertex" => {
+					let (name, words) = arguments
+						.split_first()
+						.ok_or_else(|| {
+							syntax("missing name")
+						})?;
+					let position = coordinates(words)
+						.map_err(syntax)?;
+					description
+						.vertices
+						.push((name.clone(), position));
+				}
+				"edge" => match arguments {
+					[a, b] => description
+						.edges
+						.push((a.clone(), b.clone())),
+					_ => {
+						return Err(syntax(
+							"expected 2 vertices",
+						))
+					}
+				},
+				"star" => description
+					.star_points
+					.extend_from_slice(arguments),
+				"handicap" => description
+					.handicap
+					.extend_from_slice(arguments),
+				_ => return Err(syntax("unknown keyword")),
+			}
+		}
+		description.build()
+	}
+}
+
+/// Write the board in the format described in the module.
+impl fmt::Display for Custom {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let indexer = self.indexer();
+		let vertices: Vec<_> = (0..indexer.range())
+			.map(|n| indexer.to_index(n))
+			.collect();
+		let name = |&i: &Handle| quote(self.name(i));
+		for v in &vertices {
+			write!(f, "vertex {}", name(v))?;
+			for x in self.position(*v).unwrap_or(&[]) {
+				write!(f, " {}", x)?;
+			}
+			writeln!(f)?;
+		}
+		let adj = self.adjacencies();
+		for (j, a) in vertices.iter().enumerate() {
+			for b in &vertices[j + 1..] {
+				if adj[(*a, *b)] {
+					writeln!(
+						f,
+						"edge {} {}",
+						name(a),
+						name(b)
+					)?;
+				}
+			}
+		}
+		let lists = [
+			("star", &self.star_points),
+			("handicap", &self.handicap),
+		];
+		for &(keyword, list) in &lists {
+			if !list.is_empty() {
+				let names: Vec<_> =
+					list.iter().map(name).collect();
+				writeln!(f, "{} {}", keyword, names.join(" "))?;
+			}
+		}
+		Ok(())
+	}
+}
+
+// TODO: Throw out as soon as coverage tools can handle doctests.
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TAIL: &str = "\
+# A triangle with a tail.
+vertex a 0 0
+vertex b 2 0
+vertex c 1 1.5 # The top.
+vertex \"the tail\" 1 3
+edge a b
+edge b c
+edge c a
+edge c \"the tail\"
+star c
+handicap c a
+handicap b
+";
+
+	#[test]
+	fn doctests() {
+		let input = "vertex a 0 0\nvertex b 1 0\nedge a b\nstar a\n";
+		let board: Custom = input.parse().unwrap();
+		let (a, b) = (
+			board.vertex("a").unwrap(),
+			board.vertex("b").unwrap(),
+		);
+		assert!(board.adjacencies()[(a, b)]);
+		assert!(board.is_hoshi(a));
+		assert_eq!(board.to_string(), input);
+	}
+
+	#[test]
+	fn load() {
+		let board: Custom = TAIL.parse().unwrap();
+		let v = |name| board.vertex(name).unwrap();
+		let adj = board.adjacencies();
+		assert!(adj[(v("a"), v("c"))]);
+		assert!(adj[(v("the tail"), v("the tail"))]);
+		assert!(!adj[(v("a"), v("the tail"))]);
+		assert_eq!(board.position(v("c")), Some(&[1.0, 1.5][..]));
+		assert_eq!(board.star_points(), &[v("c")]);
+		assert_eq!(board.handicap(), &[v("c"), v("a"), v("b")]);
+		assert_eq!(board.name(v("the tail")), "the tail");
+	}
+
+	#[test]
+	fn round_trip() {
+		let board: Custom = TAIL.parse().unwrap();
+		let written = board.to_string();
+		assert_eq!(
+			written,
+			"vertex a 0 0\nvertex b 2 0\nvertex c 1 1.5\n\
+			 vertex \"the tail\" 1 3\n\
+			 edge a b\nedge a c\nedge b c\nedge c \"the tail\"\n\
+			 star c\nhandicap c a b\n",
+		);
+		assert_eq!(written.parse::<Custom>().unwrap(), board);
+		let odd: Custom = "vertex \"say \\\"hi\\\"\"\nvertex \"\"\n\
+		                   edge \"\" \"say \\\"hi\\\"\""
+			.parse()
+			.unwrap();
+		assert_eq!(odd.name(odd.indexer().to_index(0)), "say \"hi\"");
+		assert_eq!(odd.to_string().parse::<Custom>().unwrap(), odd);
+	}
+
+	#[test]
+	fn load_errors() {
+		let error = |input: &str| input.parse::<Custom>().unwrap_err();
+		assert_eq!(
+			error("vertex a\nvertex a"),
+			LoadError::DuplicateVertex("a".to_string())
+		);
+		assert_eq!(
+			error("vertex a\nedge a b"),
+			LoadError::UnknownVertex("b".to_string())
+		);
+		assert_eq!(
+			error("vertex a\nstar b"),
+			LoadError::UnknownVertex("b".to_string())
+		);
+		assert_eq!(
+			error("vertex a 0 0\nvertex b 0 0 0"),
+			LoadError::Layout("b".to_string())
+		);
+		assert_eq!(
+			error("vertex a\n\nvertex b 0"),
+			LoadError::Syntax(
+				3,
+				"expected 2 or 3 coordinates".to_string()
+			)
+		);
+		assert_eq!(
+			error("vertex a x 0"),
+			LoadError::Syntax(1, "invalid coordinate".to_string())
+		);
+		assert_eq!(
+			error("vertex a NaN 0"),
+			LoadError::Syntax(1, "invalid coordinate".to_string())
+		);
+		assert_eq!(
+			error("vertex \"a"),
+			LoadError::Syntax(1, "unterminated quote".to_string())
+		);
+		assert_eq!(
+			error("vertex a\nedge a"),
+			LoadError::Syntax(2, "expected 2 vertices".to_string())
+		);
+		assert_eq!(
+			error("vertex a\nside a"),
+			LoadError::Syntax(2, "unknown keyword".to_string())
+		);
+		assert_eq!(
+			error("vertex a\nedge a b").to_string(),
+			"unknown vertex \"b\""
+		);
+		assert_eq!(
+			error("vertex a\nvertex b\nstar a b\nstar a"),
+			LoadError::DuplicateStarPoint("a".to_string())
+		);
+		assert_eq!(
+			error("vertex a\nvertex b\nhandicap a b a"),
+			LoadError::DuplicateHandicap("a".to_string())
+		);
+	}
+}
@@ -0,0 +1,245 @@
+//! Go on directed graphs.
+//! A stone only breathes through the points it points to,
+//! and whether two stones pointing at each other in one direction only
+//! belong to the same group is a choice of rules.
+//! On symmetric boards, all of this reduces to ordinary Go.
+
+use super::{irreflexivity, Board, GraphError};
+use core::util::bool_mat::BoolMat;
+use core::util::indexer::Indexer;
+use core::util::linear_map::LinearMap;
+use std::marker::PhantomData;
+
+/// The ways stones may connect along directed adjacencies.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Connectivity {
+	/// Two stones are connected
+	/// if each can be reached from the other
+	/// through stones of the same colour.
+	Strong,
+	/// Two stones are connected
+	/// if one can be reached from the other
+	/// through stones of the same colour,
+	/// ignoring the direction of the adjacencies.
+	Weak,
+}
+
+/// A marker for boards whose adjacency need not be symmetric,
+/// for code which only makes sense on those.
+pub trait Directed: Board {}
+
+/// The most generic directed board: A directed graph.
+/// Unlike `Graph`, the adjacencies need not go both ways,
+/// but every vertex still has to be adjacent to itself.
+///
+/// # Examples
+///
+/// ```
+/// # use gorrosion::core::board::{Board, Connectivity, Digraph, Graph};
+/// # use gorrosion::core::util::bool_vec::BoolVec;
+/// // A one-way street 0 → 1 → 2.
+/// let street: Digraph<_> = Graph::builder(3)
+///	.arc(0, 1)
+///	.arc(1, 2)
+///	.build_directed(Connectivity::Strong)
+///	.unwrap();
+/// assert!(!street.is_symmetric());
+/// let stones = BoolVec::trues(3);
+/// // Stones connect only when reachable in both directions.
+/// assert!(!street.connections(&stones)[(0, 1)]);
+/// ```
+#[derive(PartialEq, Eq, Debug)]
+pub struct Digraph<I: Indexer, A: LinearMap<I, I> = BoolMat<I, I>> {
+	adj: A,
+	indexer: PhantomData<I>,
+	connectivity: Connectivity,
+}
+
+impl<I, A> Digraph<I, A>
+where
+	I: Indexer,
+	A: LinearMap<I, I>,
+{
+	/// Use an existing adjacency matrix as directed board.
+	/// Every vertex has to be adjacent to itself.
+	pub fn new(
+		adj: A,
+		connectivity: Connectivity,
+	) -> Result<Self, GraphError<I::Index>> {
+		irreflexivity(&adj)?;
+		let indexer = PhantomData;
+		Ok(Digraph {
+			adj,
+			indexer,
+			connectivity,
+		})
+	}
+}
+
+impl<I, A> Board for Digraph<I, A>
+where
+	I: Indexer,
+	A: LinearMap<I, I>,
+{
+	type I = I;
+	type Adj = A;
+
+	fn adjacencies(&self) -> &A {
+		&self.adj
+	}
+
+	fn is_hoshi(&self, _i: I::Index) -> bool {
+		false
+	}
+
+	fn connectivity(&self) -> Connectivity {
+		self.connectivity
+	}
+}
+
+impl<I, A> Directed for Digraph<I, A>
+where
+	I: Indexer,
+	A: LinearMap<I, I>,
+{
+}
+
+// TODO: Throw out as soon as coverage tools can handle doctests.
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::board::{Graph, Rect};
+	use core::util::bool_vec::BoolVec;
+	use core::util::sparse_mat::SparseBoolMat;
+
+	#[test]
+	fn doctests() {
+		let street: Digraph<_> = Graph::builder(3)
+			.arc(0, 1)
+			.arc(1, 2)
+			.build_directed(Connectivity::Strong)
+			.unwrap();
+		assert!(!street.is_symmetric());
+		let stones = BoolVec::trues(3);
+		assert!(!street.connections(&stones)[(0, 1)]);
+	}
+
+	/// A directed cycle 0 → 1 → … → n-1 → 0.
+	fn cycle(
+		length: usize,
+		connectivity: Connectivity,
+	) -> Digraph<usize, SparseBoolMat<usize, usize>> {
+		let arcs = (0..length).map(|k| (k, (k + 1) % length));
+		arcs.fold(Graph::builder(length), |b, (j, k)| b.arc(j, k))
+			.build_directed(connectivity)
+			.unwrap()
+	}
+
+	#[test]
+	fn connectivity() {
+		let mut stones = BoolVec::trues(5);
+		stones[4] = false;
+		for &connectivity in &[Connectivity::Strong, Connectivity::Weak]
+		{
+			let board = cycle(5, connectivity);
+			let conn = board.connections(&stones);
+			// Without the last point, the cycle is a one-way street.
+			assert_eq!(
+				conn[(0, 3)],
+				connectivity == Connectivity::Weak
+			);
+			assert_eq!(
+				conn[(3, 0)],
+				connectivity == Connectivity::Weak
+			);
+			assert!(conn[(2, 2)]);
+			assert!(!conn[(4, 4)]);
+			// The full cycle is strongly connected.
+			let conn = board.connections(&BoolVec::trues(5));
+			assert!(conn[(0, 3)]);
+			assert!(conn[(3, 0)]);
+		}
+	}
+
+	#[test]
+	fn components_agree_with_closure() {
+		// Some scrambled arcs on twelve points.
+		let arcs = (0..30).map(|k| ((k * 7) % 12, (k * k + 5) % 12));
+		for &connectivity in &[Connectivity::Strong, Connectivity::Weak]
+		{
+			let board: Digraph<_, SparseBoolMat<_, _>> = arcs
+				.clone()
+				.fold(Graph::builder(12), |b, (j, k)| {
+					b.arc(j, k)
+				})
+				.build_directed(connectivity)
+				.unwrap();
+			let adj = board.adjacencies().to_dense();
+			for mask in &[0xfff, 0x5a5, 0x3c7, 0x0f0] {
+				let stones = BoolVec::from_data(
+					(0..12).map(|n| mask >> n & 1 == 1)
+						.collect(),
+					12,
+				);
+				let expected = match connectivity {
+					Connectivity::Strong => {
+						let reach = adj
+							.closure_restricted(
+								&stones,
+							);
+						reach.intersection(
+							&reach.transpose(),
+						)
+					}
+					Connectivity::Weak => (&adj + &adj
+						.transpose())
+						.closure_restricted(&stones),
+				};
+				assert_eq!(
+					board.connections(&stones),
+					expected
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn symmetric_boards() {
+		let rect = Rect::new(3, 3);
+		assert!(rect.is_symmetric());
+		let adj = rect.adjacencies().clone();
+		let weak = Digraph::new(adj, Connectivity::Weak).unwrap();
+		assert!(weak.is_symmetric());
+		let mut stones =
+			BoolVec::falses(weak.adjacencies().rows().clone());
+		stones[(0, 0)] = true;
+		stones[(0, 1)] = true;
+		stones[(2, 2)] = true;
+		assert_eq!(
+			weak.connections(&stones),
+			rect.connections(&stones)
+		);
+		assert!(rect.connections(&stones)[((0, 1), (0, 0))]);
+		assert!(!rect.connections(&stones)[((0, 1), (2, 2))]);
+	}
+
+	#[test]
+	fn validation() {
+		let mut adj = BoolMat::id_matrix(2);
+		adj[(0, 1)] = true;
+		assert_eq!(
+			Graph::new(adj.clone()),
+			Err(GraphError::Asymmetric(0, 1))
+		);
+		assert!(Digraph::new(adj.clone(), Connectivity::Weak).is_ok());
+		adj[(1, 1)] = false;
+		assert_eq!(
+			Digraph::new(adj, Connectivity::Weak),
+			Err(GraphError::Irreflexive(1))
+		);
+		let res: Result<Digraph<_>, _> = Graph::builder(2)
+			.arc(0, 2)
+			.build_directed(Connectivity::Strong);
+		assert_eq!(res, Err(GraphError::InvalidVertex(2)));
+	}
+}
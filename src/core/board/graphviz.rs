@@ -0,0 +1,302 @@
+//! Export boards to GraphViz DOT,
+//! since adjacency matrices are hard to read
+//! for anything but the smallest boards.
+//! Render the output with `neato` or `dot`.
+
+use super::Board;
+use core::util::bool_vec::BoolVec;
+use core::util::indexer::Indexer;
+use core::util::linear_map::LinearMap;
+use std::collections::HashMap;
+use std::fmt;
+
+/// The number of colours in the GraphViz colour scheme
+/// used to tell groups apart.
+const GROUP_COLOURS: usize = 9;
+
+/// The colour of a vertex in the exported graph.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Stone {
+	Empty,
+	Black,
+	White,
+}
+
+/// A board, optionally together with a position on it,
+/// ready to be written as a graph in DOT.
+/// Vertices are labelled by the `Debug` representation of their index,
+/// edges are taken from the adjacency matrix without the loops.
+/// Boards whose adjacencies do not all go both ways
+/// are written as directed graphs with an arc for every adjacency.
+///
+/// # Examples
+///
+/// ```
+/// # use gorrosion::core::board::{Dot, Rect};
+/// # use gorrosion::core::util::bool_vec::BoolVec;
+/// # use gorrosion::core::util::indexer;
+/// let board = Rect::new(1, 2);
+/// let mut black = BoolVec::falses(indexer::Rect::new(1, 2));
+/// black[(0, 1)] = true;
+/// let white = BoolVec::falses(indexer::Rect::new(1, 2));
+/// let dot = Dot::new(&board).stones(&black, &white);
+/// assert_eq!(
+///	dot.to_string(),
+///	"graph {\n\
+///	 \t0 [label=\"(0, 0)\"];\n\
+///	 \t1 [label=\"(0, 1)\", style=filled, fillcolor=black, fontcolor=white];\n\
+///	 \t0 -- 1;\n\
+///	 }\n",
+/// );
+/// ```
+pub struct Dot<'a, B: Board + 'a> {
+	board: &'a B,
+	stones: Vec<Stone>,
+	groups: Vec<Option<usize>>,
+}
+
+impl<'a, B> Dot<'a, B>
+where
+	B: Board,
+{
+	/// Prepare the export of an empty board.
+	pub fn new(board: &'a B) -> Dot<'a, B> {
+		let size = board.adjacencies().rows().range();
+		let stones = vec![Stone::Empty; size];
+		let groups = vec![None; size];
+		Dot {
+			board,
+			stones,
+			groups,
+		}
+	}
+
+	/// Fill the vertices occupied by stones with their colour.
+	/// No vertex may hold stones of both colours.
+	pub fn stones(
+		mut self,
+		black: &BoolVec<B::I>,
+		white: &BoolVec<B::I>,
+	) -> Self {
+		let indexer = self.board.adjacencies().rows();
+		for (n, stone) in self.stones.iter_mut().enumerate() {
+			let i = indexer.to_index(n);
+			*stone = match (black[i], white[i]) {
+				(true, true) => {
+					panic!("vertex {} is both black and white", n)
+				}
+				(true, false) => Stone::Black,
+				(false, true) => Stone::White,
+				(false, false) => Stone::Empty,
+			};
+		}
+		self
+	}
+
+	/// Outline the groups given by a `connections` matrix,
+	/// which relates every stone to the stones of its group,
+	/// in a different colour for each group,
+	/// as far as the colour scheme allows.
+	/// Vertices related to nothing belong to no group.
+	/// Edges within a group are drawn in its colour as well.
+	pub fn groups<L>(mut self, connections: &L) -> Self
+	where
+		L: LinearMap<B::I, B::I>,
+	{
+		let indexer = self.board.adjacencies().rows().clone();
+		for &(a, b) in &connections.entries() {
+			let (j, k) = (indexer.to_num(a), indexer.to_num(b));
+			if self.groups[j].is_none() || self.groups[j] > Some(k)
+			{
+				self.groups[j] = Some(k);
+			}
+		}
+		// Number the groups by their first vertex.
+		let mut numbers = HashMap::new();
+		for group in self.groups.iter_mut().flatten() {
+			let next = numbers.len();
+			*group = *numbers.entry(*group).or_insert(next);
+		}
+		self
+	}
+
+	/// The attributes outlining a member of a group.
+	fn group_attributes(&self, n: usize) -> Option<String> {
+		self.groups[n].map(|group| {
+			let colour = group % GROUP_COLOURS + 1;
+			format!(
+				"colorscheme=set19, color={}, penwidth=3",
+				colour
+			)
+		})
+	}
+}
+
+/// Quote a string for DOT.
+fn quote(s: &str) -> String {
+	format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl<'a, B> fmt::Display for Dot<'a, B>
+where
+	B: Board,
+	<B::I as Indexer>::Index: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let adj = self.board.adjacencies();
+		let indexer = adj.rows();
+		let directed = !self.board.is_symmetric();
+		let (kind, arrow) = if directed {
+			("digraph", "->")
+		} else {
+			("graph", "--")
+		};
+		writeln!(f, "{} {{", kind)?;
+		for n in 0..indexer.range() {
+			let label = format!("{:?}", indexer.to_index(n));
+			let mut attributes =
+				vec![format!("label={}", quote(&label))];
+			match self.stones[n] {
+				Stone::Black => attributes.push(
+					"style=filled, fillcolor=black, fontcolor=white".to_string(),
+				),
+				Stone::White => attributes
+					.push("style=filled, fillcolor=white".to_string()),
+				Stone::Empty => {}
+			}
+			attributes.extend(self.group_attributes(n));
+			writeln!(f, "\t{} [{}];", n, attributes.join(", "))?;
+		}
+		let mut edges: Vec<_> = adj
+			.entries()
+			.into_iter()
+			.map(|(a, b)| (indexer.to_num(a), indexer.to_num(b)))
+			.filter(|&(j, k)| if directed { j != k } else { j < k })
+			.collect();
+		edges.sort();
+		for (j, k) in edges {
+			match self.group_attributes(j) {
+				Some(attributes)
+					if self.groups[j] == self.groups[k] =>
+				{
+					writeln!(
+						f,
+						"\t{} {} {} [{}];",
+						j, arrow, k, attributes
+					)?
+				}
+				_ => writeln!(f, "\t{} {} {};", j, arrow, k)?,
+			}
+		}
+		writeln!(f, "}}")
+	}
+}
+
+// TODO: Throw out as soon as coverage tools can handle doctests.
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::board::{Connectivity, Custom, Digraph, Graph, Rect};
+	use core::util::bool_mat::BoolMat;
+	use core::util::indexer;
+	use core::util::sparse_mat::SparseBoolMat;
+
+	#[test]
+	fn doctests() {
+		let board = Rect::new(1, 2);
+		let mut black = BoolVec::falses(indexer::Rect::new(1, 2));
+		black[(0, 1)] = true;
+		let white = BoolVec::falses(indexer::Rect::new(1, 2));
+		let dot = Dot::new(&board).stones(&black, &white);
+		assert_eq!(
+			dot.to_string(),
+			"graph {\n\
+			 \t0 [label=\"(0, 0)\"];\n\
+			 \t1 [label=\"(0, 1)\", style=filled, fillcolor=black, fontcolor=white];\n\
+			 \t0 -- 1;\n\
+			 }\n",
+		);
+	}
+
+	#[test]
+	fn groups() {
+		let board = Rect::new(1, 4);
+		let rect = indexer::Rect::new(1, 4);
+		let mut white = BoolVec::falses(rect.clone());
+		white[(0, 0)] = true;
+		white[(0, 1)] = true;
+		white[(0, 3)] = true;
+		let black = BoolVec::falses(rect.clone());
+		let group = [(0, 0), (0, 1)];
+		let mut connections = BoolMat::falses(rect.clone(), rect);
+		for &a in &group {
+			for &b in &group {
+				connections[(a, b)] = true;
+			}
+		}
+		connections[((0, 3), (0, 3))] = true;
+		let sparse = SparseBoolMat::from_dense(&connections);
+		let dot =
+			Dot::new(&board).stones(&black, &white).groups(&sparse);
+		let first = "colorscheme=set19, color=1, penwidth=3";
+		let second = "colorscheme=set19, color=2, penwidth=3";
+		let filled = "style=filled, fillcolor=white";
+		assert_eq!(
+			dot.to_string(),
+			format!(
+				"graph {{\n\
+				 \t0 [label=\"(0, 0)\", {1}, {0}];\n\
+				 \t1 [label=\"(0, 1)\", {1}, {0}];\n\
+				 \t2 [label=\"(0, 2)\"];\n\
+				 \t3 [label=\"(0, 3)\", {1}, {2}];\n\
+				 \t0 -- 1 [{0}];\n\
+				 \t1 -- 2;\n\
+				 \t2 -- 3;\n\
+				 }}\n",
+				first, filled, second
+			)
+		);
+	}
+
+	#[test]
+	fn directed() {
+		// Arcs pointing either way along the indexer.
+		let board: Digraph<_> = Graph::builder(3)
+			.arc(0, 1)
+			.arc(2, 1)
+			.build_directed(Connectivity::Weak)
+			.unwrap();
+		assert_eq!(
+			Dot::new(&board).to_string(),
+			"digraph {\n\
+			 \t0 [label=\"0\"];\n\
+			 \t1 [label=\"1\"];\n\
+			 \t2 [label=\"2\"];\n\
+			 \t0 -> 1;\n\
+			 \t2 -> 1;\n\
+			 }\n",
+		);
+	}
+
+	#[test]
+	fn quoting() {
+		assert_eq!(quote(r#"say "hi" \o/"#), r#""say \"hi\" \\o/""#);
+	}
+
+	#[test]
+	fn round_trip() {
+		let dot = Dot::new(&Rect::new(2, 2)).to_string();
+		let board = Custom::from_dot(&dot).unwrap();
+		let v = |name| board.vertex(name).unwrap();
+		assert!(board.adjacencies()[(v("0"), v("2"))]);
+		assert!(!board.adjacencies()[(v("0"), v("3"))]);
+	}
+
+	#[test]
+	#[should_panic]
+	fn black_and_white() {
+		let board = Rect::new(1, 2);
+		let stones = BoolVec::trues(indexer::Rect::new(1, 2));
+		Dot::new(&board).stones(&stones, &stones);
+	} // LCOV_EXCL_LINE
+}
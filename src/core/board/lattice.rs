@@ -0,0 +1,212 @@
+//! Go does not need square cells:
+//! It can just as well be played on the cells of a hexagonal tiling,
+//! where every cell has six neighbours,
+//! or on the cells of a triangular tiling,
+//! where every cell has three neighbours.
+//! Stones are placed inside the cells
+//! and cells sharing an edge are adjacent.
+
+use super::{Board, Graph};
+use core::util::bool_mat::BoolMat;
+use core::util::indexer;
+use core::util::indexer::Indexer;
+use core::util::linear_map::LinearMap;
+use std::marker::PhantomData;
+
+/// Build a graph on all valid indices of an indexer
+/// from a function listing the potential neighbours of a cell.
+/// Invalid neighbours are dropped, loops are added.
+fn tiling<I, F>(indexer: I, neighbours: F) -> Graph<I>
+where
+	I: Indexer,
+	F: Fn(I::Index) -> Vec<I::Index>,
+{
+	let entries: Vec<_> = (0..indexer.range())
+		.map(|n| indexer.to_index(n))
+		.flat_map(|i| {
+			let mut adjacent = neighbours(i);
+			adjacent.retain(|&j| indexer.is_valid(j));
+			adjacent.push(i);
+			adjacent.into_iter().map(move |j| (i, j))
+		})
+		.collect();
+	let adj = BoolMat::from_entries(indexer.clone(), indexer, entries);
+	let indexer = PhantomData;
+	Graph { adj, indexer }
+}
+
+/// A hexagon-shaped board made of hexagonal cells,
+/// using the axial coordinates of `indexer::Hexagon`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Hexagon {
+	graph: Graph<indexer::Hexagon>,
+}
+
+impl Hexagon {
+	/// Create a new hexagonal board with `side` cells along each side.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Hexagon};
+	/// let hexagon = Hexagon::new(4);
+	/// let adj = hexagon.adjacencies();
+	/// assert!(adj[((0, 0), (1, -1))]);
+	/// assert!(adj[((0, 0), (-1, 1))]);
+	/// assert!(!adj[((0, 0), (1, 1))]);
+	/// ```
+	pub fn new(side: usize) -> Hexagon {
+		let graph = tiling(indexer::Hexagon::new(side), |(q, r)| {
+			vec![
+				(q + 1, r),
+				(q - 1, r),
+				(q, r + 1),
+				(q, r - 1),
+				(q + 1, r - 1),
+				(q - 1, r + 1),
+			]
+		});
+		Hexagon { graph }
+	}
+}
+
+impl Board for Hexagon {
+	type I = indexer::Hexagon;
+	type Adj = BoolMat<Self::I, Self::I>;
+
+	fn adjacencies(&self) -> &Self::Adj {
+		self.graph.adjacencies()
+	}
+
+	fn is_hoshi(&self, _i: <Self::I as Indexer>::Index) -> bool {
+		false
+	}
+}
+
+/// A triangle-shaped board made of triangular cells,
+/// using the coordinates of `indexer::Triangle`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Triangle {
+	graph: Graph<indexer::Triangle>,
+}
+
+impl Triangle {
+	/// Create a new triangular board with `side` cells along each side,
+	/// for a total of `side * side` cells.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Triangle};
+	/// let triangle = Triangle::new(3);
+	/// let adj = triangle.adjacencies();
+	/// // The apex points up and only touches the cell below.
+	/// assert!(adj[((0, 0), (1, 1))]);
+	/// assert!(!adj[((0, 0), (1, 0))]);
+	/// assert!(adj[((1, 0), (1, 1))]);
+	/// ```
+	pub fn new(side: usize) -> Triangle {
+		let graph = tiling(indexer::Triangle::new(side), |(j, k)| {
+			let mut neighbours = vec![(j, k + 1)];
+			if k > 0 {
+				neighbours.push((j, k - 1));
+			}
+			if k % 2 == 0 {
+				neighbours.push((j + 1, k + 1));
+			} else {
+				neighbours.push((j - 1, k - 1));
+			}
+			neighbours
+		});
+		Triangle { graph }
+	}
+}
+
+impl Board for Triangle {
+	type I = indexer::Triangle;
+	type Adj = BoolMat<Self::I, Self::I>;
+
+	fn adjacencies(&self) -> &Self::Adj {
+		self.graph.adjacencies()
+	}
+
+	fn is_hoshi(&self, _i: <Self::I as Indexer>::Index) -> bool {
+		false
+	}
+}
+
+// TODO: Throw out as soon as coverage tools can handle doctests.
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::board::degrees;
+
+	#[test]
+	fn doctests() {
+		let hexagon = Hexagon::new(4);
+		let adj = hexagon.adjacencies();
+		assert!(adj[((0, 0), (1, -1))]);
+		assert!(adj[((0, 0), (-1, 1))]);
+		assert!(!adj[((0, 0), (1, 1))]);
+
+		let triangle = Triangle::new(3);
+		let adj = triangle.adjacencies();
+		assert!(adj[((0, 0), (1, 1))]);
+		assert!(!adj[((0, 0), (1, 0))]);
+		assert!(adj[((1, 0), (1, 1))]);
+	}
+
+	#[test]
+	fn hexagon_degrees() {
+		let hexagon = Hexagon::new(4);
+		let adj = hexagon.adjacencies();
+		assert_eq!(&adj.transpose(), adj);
+		let degrees = degrees(adj);
+		assert_eq!(degrees[(0, 0)], 6);
+		assert_eq!(degrees[(2, 0)], 6);
+		// Corners and sides.
+		assert_eq!(degrees[(3, 0)], 3);
+		assert_eq!(degrees[(-3, 3)], 3);
+		assert_eq!(degrees[(3, -1)], 4);
+		let total: usize = (0..adj.rows().range())
+			.map(|n| degrees[adj.rows().to_index(n)])
+			.sum();
+		// Six corners, twelve more cells on the sides
+		// and a hexagon of 19 cells inside.
+		assert_eq!(total, 6 * 3 + 12 * 4 + 19 * 6);
+	}
+
+	#[test]
+	fn hexagon_distances() {
+		// The hexagon of side n has diameter 2 (n - 1).
+		let hexagon = Hexagon::new(5);
+		let adj = hexagon.adjacencies();
+		let all = BoolMat::trues(
+			adj.rows().clone(),
+			adj.columns().clone(),
+		);
+		assert_eq!(adj.pow(8), all);
+		assert!(adj.pow(7) != all);
+	}
+
+	#[test]
+	fn triangle_degrees() {
+		let triangle = Triangle::new(4);
+		let adj = triangle.adjacencies();
+		assert_eq!(&adj.transpose(), adj);
+		let degrees = degrees(adj);
+		// The three corners only have a single neighbour.
+		assert_eq!(degrees[(0, 0)], 1);
+		assert_eq!(degrees[(3, 0)], 1);
+		assert_eq!(degrees[(3, 6)], 1);
+		assert_eq!(degrees[(3, 2)], 2);
+		assert_eq!(degrees[(2, 2)], 3);
+		assert_eq!(degrees[(2, 3)], 3);
+		// Every edge joins one of the six cells pointing down
+		// to one of the ten pointing up.
+		let total: usize = (0..adj.rows().range())
+			.map(|n| degrees[adj.rows().to_index(n)])
+			.sum();
+		assert_eq!(total, 2 * 18);
+	}
+}
@@ -0,0 +1,905 @@
+//! Go is a board game.
+//! While it is usually played on a rectangular board
+//! with a very specific structure,
+//! it can theoretically be played on a wide variety of (undirected) graphs.
+//! This module provides a suitable interface
+//! which allows the rules to be evaluated
+//! without knowledge of the exact board layout.
+
+use self::indexer::{Either, Indexer};
+use core::util::bool_mat::BoolMat;
+use core::util::bool_vec::BoolVec;
+use core::util::indexer;
+use core::util::linear_map::LinearMap;
+use core::util::permutation::Permutation;
+#[cfg(test)]
+use core::util::vec;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+mod cuboid;
+mod custom;
+mod directed;
+mod graphviz;
+mod lattice;
+mod polyhedra;
+mod region;
+mod symmetry;
+mod wrapped;
+
+pub use self::cuboid::Cuboid;
+pub use self::custom::{Custom, LoadError};
+pub use self::directed::{Connectivity, Digraph, Directed};
+pub use self::graphviz::Dot;
+pub use self::lattice::{Hexagon, Triangle};
+pub use self::polyhedra::{
+	CubeSurface, Dodecahedron, GeodesicSphere, TruncatedIcosahedron,
+};
+pub use self::region::SubBoard;
+pub use self::symmetry::{Canonical, Symmetries};
+pub use self::wrapped::{Cylinder, KleinBottle, MoebiusStrip, Torus};
+
+// TODO: We might be able to get rid of a few of those lifetimes.
+
+/// This trait captures the information needed to play Go on a board.
+pub trait Board: Eq {
+	/// The interface for addressing the intersection point.
+	/// In classical Go, this will in some way implement a square.
+	type I: Indexer;
+
+	/// The representation of the adjacency matrix.
+	/// Small boards are best served by a dense `BoolMat`,
+	/// large sparse graphs rather by a `SparseBoolMat`.
+	type Adj: LinearMap<Self::I, Self::I>;
+
+	/// The adjacency matrix of the underlying graph.
+	/// Every point has to be adjacent to itself.
+	/// On most boards, adjacency is symmetric.
+	/// On directed boards, the entry at `(a, b)` means
+	/// that an empty point `b` is a liberty of a stone on `a`,
+	/// and stones connect as described by `connectivity`.
+	fn adjacencies(&self) -> &Self::Adj;
+
+	/// This will soon go away, presumably.
+	/// However, the boards will need to provide some way
+	/// to know where to place a fixed handicap.
+	/// This will come in one of the refactors of the future.
+	fn is_hoshi(&self, i: <Self::I as Indexer>::Index) -> bool;
+
+	/// The symmetries of the board,
+	/// i.e. the permutations of its points preserving adjacency,
+	/// starting with the identity.
+	/// By default, they are searched for in the adjacency matrix,
+	/// which takes a while for large boards with many symmetries.
+	/// Boards whose symmetries are known should list them directly.
+	fn automorphisms(&self) -> Vec<Permutation<Self::I>> {
+		symmetry::automorphisms(self.adjacencies())
+	}
+
+	/// Whether every adjacency goes both ways.
+	fn is_symmetric(&self) -> bool {
+		asymmetry(self.adjacencies()).is_none()
+	}
+
+	/// How stones connect to groups along the adjacencies.
+	/// On symmetric boards, all choices agree.
+	fn connectivity(&self) -> Connectivity {
+		Connectivity::Strong
+	}
+
+	/// Label every stone of a set with its group,
+	/// by the internal index of one of the stones of the group.
+	/// Points outside the set carry no label.
+	/// The groups are found by walking along the adjacencies,
+	/// so sparse boards never need a dense matrix.
+	fn components(&self, stones: &BoolVec<Self::I>) -> Vec<Option<usize>> {
+		let indexer = stones.indexer();
+		let stones = stones.as_slice();
+		let mut out = vec![Vec::new(); indexer.range()];
+		let mut into = vec![Vec::new(); indexer.range()];
+		for (a, b) in self.adjacencies().entries() {
+			let (j, k) = (indexer.to_num(a), indexer.to_num(b));
+			if j != k && stones[j] && stones[k] {
+				out[j].push(k);
+				into[k].push(j);
+			}
+		}
+		let labels = match self.connectivity() {
+			Connectivity::Strong => strong_components(&out, &into),
+			Connectivity::Weak => {
+				// Ignoring the directions,
+				// the strong components are the weak ones.
+				for (list, back) in out.iter_mut().zip(into) {
+					list.extend(back);
+				}
+				strong_components(&out, &out)
+			}
+		};
+		labels.into_iter()
+			.zip(stones)
+			.map(|(label, &stone)| label.filter(|_| stone))
+			.collect()
+	}
+
+	/// Relate every stone of a set
+	/// to the stones of the same group.
+	/// Points outside the set are not related to anything.
+	/// This takes memory quadratic in the size of the board,
+	/// `components` does not.
+	fn connections(
+		&self,
+		stones: &BoolVec<Self::I>,
+	) -> BoolMat<Self::I, Self::I> {
+		let indexer = stones.indexer().clone();
+		let mut members = vec![Vec::new(); indexer.range()];
+		for (n, label) in
+			self.components(stones).into_iter().enumerate()
+		{
+			if let Some(label) = label {
+				members[label].push(indexer.to_index(n));
+			}
+		}
+		let mut connections = BoolMat::falses(indexer.clone(), indexer);
+		for group in &members {
+			for &a in group {
+				for &b in group {
+					connections[(a, b)] = true;
+				}
+			}
+		}
+		connections
+	}
+}
+
+/// The most generic board: A graph.
+/// All other boards could theoretically be implemented
+/// using this as a basis.
+/// The adjacency matrix may be stored in any representation.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Graph<I: Indexer, A: LinearMap<I, I> = BoolMat<I, I>> {
+	adj: A,
+	indexer: PhantomData<I>,
+}
+
+impl<I, A> Board for Graph<I, A>
+where
+	I: Indexer,
+	A: LinearMap<I, I>,
+{
+	type I = I;
+	type Adj = A;
+
+	fn adjacencies(&self) -> &A {
+		&self.adj
+	}
+
+	fn is_hoshi(&self, _i: I::Index) -> bool {
+		false
+	}
+}
+
+impl<I, A> Graph<I, A>
+where
+	I: Indexer,
+	A: LinearMap<I, I>,
+{
+	/// Use an existing adjacency matrix as board.
+	/// It has to be symmetric,
+	/// and every vertex has to be adjacent to itself,
+	/// since the rules rely on that to find the groups.
+	pub fn new(adj: A) -> Result<Self, GraphError<I::Index>> {
+		irreflexivity(&adj)?;
+		if let Some((a, b)) = asymmetry(&adj) {
+			return Err(GraphError::Asymmetric(a, b));
+		}
+		let indexer = PhantomData;
+		Ok(Graph { adj, indexer })
+	}
+}
+
+/// Find a point which is not adjacent to itself.
+fn irreflexivity<I, A>(adj: &A) -> Result<(), GraphError<I::Index>>
+where
+	I: Indexer,
+	A: LinearMap<I, I>,
+{
+	let indexer = adj.rows();
+	if indexer != adj.columns() {
+		return Err(GraphError::NonSquare);
+	}
+	for n in 0..indexer.range() {
+		let i = indexer.to_index(n);
+		if !adj.entry(i, i) {
+			return Err(GraphError::Irreflexive(i));
+		}
+	}
+	Ok(())
+}
+
+/// The strongly connected components of a graph,
+/// given by the internal indices of the successors
+/// and predecessors of every vertex,
+/// each labelled by one of its vertices.
+/// Vertices without any arcs form components of their own,
+/// which the caller may ignore.
+fn strong_components(
+	successors: &[Vec<usize>],
+	predecessors: &[Vec<usize>],
+) -> Vec<Option<usize>> {
+	let range = successors.len();
+	// First, list the vertices in the order
+	// a depth-first search is done with them.
+	let mut visited = vec![false; range];
+	let mut finished = Vec::with_capacity(range);
+	for root in 0..range {
+		if visited[root] {
+			continue;
+		}
+		visited[root] = true;
+		let mut stack = vec![(root, 0)];
+		while let Some(&(j, next)) = stack.last() {
+			match successors[j].get(next) {
+				Some(&k) => {
+					let top = stack.len() - 1;
+					stack[top].1 += 1;
+					if !visited[k] {
+						visited[k] = true;
+						stack.push((k, 0));
+					}
+				}
+				None => {
+					finished.push(j);
+					stack.pop();
+				}
+			}
+		}
+	}
+	// Then, going backwards from the vertex done with last
+	// reaches exactly the vertices of its component.
+	let mut labels = vec![None; range];
+	for &root in finished.iter().rev() {
+		if labels[root].is_some() {
+			continue;
+		}
+		labels[root] = Some(root);
+		let mut todo = vec![root];
+		while let Some(j) = todo.pop() {
+			for &k in &predecessors[j] {
+				if labels[k].is_none() {
+					labels[k] = Some(root);
+					todo.push(k);
+				}
+			}
+		}
+	}
+	labels
+}
+
+/// Find an adjacency which does not go both ways.
+fn asymmetry<I, A>(adj: &A) -> Option<(I::Index, I::Index)>
+where
+	I: Indexer,
+	A: LinearMap<I, I>,
+{
+	let indexer = adj.rows();
+	let entries = adj.entries();
+	let arcs: HashSet<_> = entries
+		.iter()
+		.map(|&(a, b)| (indexer.to_num(a), indexer.to_num(b)))
+		.collect();
+	entries.into_iter().find(|&(a, b)| {
+		let (j, k) = (indexer.to_num(a), indexer.to_num(b));
+		!arcs.contains(&(k, j))
+	})
+}
+
+impl<I> Graph<I>
+where
+	I: Indexer,
+{
+	/// Start building a graph on the vertices of an indexer.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Graph};
+	/// # use gorrosion::core::util::indexer::{Indexer, Labelled};
+	/// # use gorrosion::core::util::linear_map::LinearMap;
+	/// # use gorrosion::core::util::sparse_mat::SparseBoolMat;
+	/// let labels = Labelled::new(vec!["A", "B", "C"]);
+	/// let v = |l| labels.handle(l).unwrap();
+	/// let triangle: Graph<_, SparseBoolMat<_, _>> = Graph::builder(labels.clone())
+	///	.edges(vec![(v("A"), v("B")), (v("B"), v("C")), (v("C"), v("A"))])
+	///	.build()
+	///	.unwrap();
+	/// assert!(triangle.adjacencies().entry(v("A"), v("C")));
+	/// assert!(triangle.adjacencies().entry(v("B"), v("B")));
+	/// ```
+	pub fn builder(indexer: I) -> GraphBuilder<I> {
+		GraphBuilder::new(indexer)
+	}
+}
+
+/// The reasons a graph might not make for a valid board.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum GraphError<Index> {
+	/// The vertex is not known to the indexer.
+	InvalidVertex(Index),
+	/// There is an edge from the first vertex to the second
+	/// but not back.
+	Asymmetric(Index, Index),
+	/// The vertex is not adjacent to itself.
+	Irreflexive(Index),
+	/// The rows and columns of the adjacency matrix
+	/// are indexed differently.
+	NonSquare,
+}
+
+impl<Index: Debug> fmt::Display for GraphError<Index> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			GraphError::InvalidVertex(i) => {
+				write!(f, "invalid vertex {:?}", i)
+			}
+			GraphError::Asymmetric(a, b) => {
+				write!(
+					f,
+					"edge from {:?} to {:?} but not back",
+					a, b
+				)
+			}
+			GraphError::Irreflexive(i) => {
+				write!(
+					f,
+					"vertex {:?} not adjacent to itself",
+					i
+				)
+			}
+			GraphError::NonSquare => {
+				write!(
+					f,
+					"rows and columns indexed differently"
+				)
+			}
+		}
+	}
+}
+
+impl<Index: Debug> Error for GraphError<Index> {}
+
+/// Collects the edges of a graph
+/// given as edge list or as adjacency lists,
+/// and checks them for validity once everything is known.
+/// Every vertex is made adjacent to itself automatically.
+#[derive(Debug, Clone)]
+pub struct GraphBuilder<I: Indexer> {
+	indexer: I,
+	arcs: Vec<(I::Index, I::Index)>,
+}
+
+impl<I> GraphBuilder<I>
+where
+	I: Indexer,
+{
+	pub fn new(indexer: I) -> GraphBuilder<I> {
+		let arcs = Vec::new();
+		GraphBuilder { indexer, arcs }
+	}
+
+	/// Add an undirected edge between two vertices.
+	pub fn edge(mut self, a: I::Index, b: I::Index) -> Self {
+		self.arcs.push((a, b));
+		self.arcs.push((b, a));
+		self
+	}
+
+	/// Add undirected edges between pairs of vertices.
+	pub fn edges<E>(self, edges: E) -> Self
+	where
+		E: IntoIterator<Item = (I::Index, I::Index)>,
+	{
+		edges.into_iter().fold(self, |b, (v, w)| b.edge(v, w))
+	}
+
+	/// Add the list of neighbours of a vertex.
+	/// For the result to be a valid board,
+	/// the vertex has to appear in the lists of all its neighbours.
+	pub fn adjacency_list<N>(
+		mut self,
+		vertex: I::Index,
+		neighbours: N,
+	) -> Self
+	where
+		N: IntoIterator<Item = I::Index>,
+	{
+		let arcs = neighbours.into_iter().map(|n| (vertex, n));
+		self.arcs.extend(arcs);
+		self
+	}
+
+	/// Add an edge in one direction only,
+	/// for building directed boards.
+	pub fn arc(mut self, from: I::Index, to: I::Index) -> Self {
+		self.arcs.push((from, to));
+		self
+	}
+
+	/// Check everything and build the graph.
+	pub fn build<A>(self) -> Result<Graph<I, A>, GraphError<I::Index>>
+	where
+		A: LinearMap<I, I>,
+	{
+		Graph::new(self.matrix()?)
+	}
+
+	/// Check everything and build a directed graph
+	/// whose stones connect as given.
+	pub fn build_directed<A>(
+		self,
+		connectivity: Connectivity,
+	) -> Result<Digraph<I, A>, GraphError<I::Index>>
+	where
+		A: LinearMap<I, I>,
+	{
+		Digraph::new(self.matrix()?, connectivity)
+	}
+
+	/// Check the vertices and assemble the adjacency matrix.
+	fn matrix<A>(self) -> Result<A, GraphError<I::Index>>
+	where
+		A: LinearMap<I, I>,
+	{
+		let indexer = self.indexer;
+		for &(a, b) in &self.arcs {
+			for &i in &[a, b] {
+				if !indexer.is_valid(i) {
+					return Err(GraphError::InvalidVertex(
+						i,
+					));
+				}
+			}
+		}
+		let loops = (0..indexer.range()).map(|n| {
+			let i = indexer.to_index(n);
+			(i, i)
+		});
+		let entries: Vec<_> =
+			self.arcs.into_iter().chain(loops).collect();
+		Ok(A::from_entries(indexer.clone(), indexer, entries))
+	}
+}
+
+/// The adjacency matrix of a path with the given number of vertices,
+/// including the reflexive adjacencies.
+/// ```none
+/// ┼─┼─┼─┼
+/// ```
+fn path(length: usize) -> BoolMat<usize, usize> {
+	let edges = (1..length).flat_map(|k| vec![(k - 1, k), (k, k - 1)]);
+	let loops = (0..length).map(|k| (k, k));
+	BoolMat::from_entries(length, length, edges.chain(loops))
+}
+
+/// The adjacency matrix of a cycle with the given number of vertices,
+/// including the reflexive adjacencies.
+/// This is a path whose ends are glued together.
+fn cycle(length: usize) -> BoolMat<usize, usize> {
+	let mut adj = path(length);
+	if length > 0 {
+		adj[(0, length - 1)] = true;
+		adj[(length - 1, 0)] = true;
+	}
+	adj
+}
+
+/// The box drawing character for an intersection point
+/// with lines leaving in the given directions.
+fn junction(up: bool, down: bool, left: bool, right: bool) -> char {
+	match (up, down, left, right) {
+		(true, true, true, true) => '┼',
+		(true, true, true, false) => '┤',
+		(true, true, false, true) => '├',
+		(true, false, true, true) => '┴',
+		(false, true, true, true) => '┬',
+		(true, true, false, false) => '│',
+		(false, false, true, true) => '─',
+		(true, false, true, false) => '┘',
+		(true, false, false, true) => '└',
+		(false, true, true, false) => '┐',
+		(false, true, false, true) => '┌',
+		(true, false, false, false) => '╵',
+		(false, true, false, false) => '╷',
+		(false, false, true, false) => '╴',
+		(false, false, false, true) => '╶',
+		(false, false, false, false) => '·',
+	}
+}
+
+/// Rectangular boards with the classical line pattern.
+/// ```none
+/// ┼─┼─┼─┼
+/// ┼─┼─┼─┼
+/// ┼─┼─┼─┼
+/// ```
+#[derive(PartialEq, Eq, Debug)]
+pub struct Rect {
+	graph: Graph<indexer::Rect>,
+}
+
+impl Rect {
+	/// Create a new rectangular board with the given measurements.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::Rect;
+	/// # use gorrosion::core::board::Board;
+	/// let rect = Rect::new(6, 4);
+	/// // Get the adjacency matrix.
+	/// let adj = rect.adjacencies();
+	/// // Every intersection point can be reached from every intersection point
+	/// // in at most (6-1) + (4-1) = 8 steps.
+	/// let two_steps = adj * adj;
+	/// let four_steps = &two_steps * &two_steps;
+	/// // So, this should be the all-true matrix
+	/// let eight_steps = &four_steps * &four_steps;
+	/// assert!(eight_steps[((0, 0), (5, 3))]);
+	/// ```
+	pub fn new(height: usize, width: usize) -> Rect {
+		let indexer = indexer::Rect::new(height, width);
+		// The grid is the Cartesian product of two paths.
+		let adj = path(height).kronecker_sum(&path(width));
+		let adj = adj.reindex(indexer.clone(), indexer);
+		let indexer = PhantomData;
+		let graph = Graph { adj, indexer };
+		Rect { graph }
+	}
+}
+
+impl Board for Rect {
+	type I = indexer::Rect;
+	type Adj = BoolMat<Self::I, Self::I>;
+
+	fn adjacencies(&self) -> &BoolMat<Self::I, Self::I> {
+		self.graph.adjacencies()
+	}
+
+	fn is_hoshi(&self, _i: <Self::I as Indexer>::Index) -> bool {
+		false
+	}
+
+	/// The reflections in the middle row and column,
+	/// the rotation by a half turn
+	/// and, if the board is square,
+	/// the reflections in the diagonals
+	/// and the rotations by a quarter turn.
+	/// Boards of width or height one have fewer symmetries.
+	fn automorphisms(&self) -> Vec<Permutation<Self::I>> {
+		let indexer = self.adjacencies().rows();
+		let (height, width) = (indexer.height(), indexer.width());
+		let transposes: &[bool] = if height == width {
+			&[false, true]
+		} else {
+			&[false]
+		};
+		let mut symmetries = Vec::new();
+		for &transpose in transposes {
+			for &(flip_rows, flip_columns) in &[
+				(false, false),
+				(true, false),
+				(false, true),
+				(true, true),
+			] {
+				let symmetry = Permutation::from_fn(
+					indexer.clone(),
+					|(j, k)| {
+						let (j, k) = if transpose {
+							(k, j)
+						} else {
+							(j, k)
+						};
+						let j = if flip_rows {
+							height - 1 - j
+						} else {
+							j
+						};
+						let k = if flip_columns {
+							width - 1 - k
+						} else {
+							k
+						};
+						(j, k)
+					},
+				);
+				let symmetry = symmetry
+					.expect("symmetries are bijective");
+				if !symmetries.contains(&symmetry) {
+					symmetries.push(symmetry);
+				}
+			}
+		}
+		symmetries
+	}
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct Square {
+	rect: Rect,
+}
+
+impl Square {
+	pub fn new(length: usize) -> Square {
+		let rect = Rect::new(length, length);
+		Square { rect }
+	}
+}
+
+impl Board for Square {
+	type I = indexer::Rect;
+	type Adj = BoolMat<Self::I, Self::I>;
+
+	fn adjacencies(&self) -> &BoolMat<Self::I, Self::I> {
+		self.rect.adjacencies()
+	}
+
+	fn is_hoshi(&self, _i: <Self::I as Indexer>::Index) -> bool {
+		false
+	}
+
+	fn automorphisms(&self) -> Vec<Permutation<Self::I>> {
+		self.rect.automorphisms()
+	}
+}
+
+/// Two boards side by side without any connection between them,
+/// e.g. for playing two games simultaneously
+/// or for providing a bench next to the actual board.
+/// The adjacency matrix is block-diagonal.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Sum<A: Board, B: Board> {
+	left: A,
+	right: B,
+	graph: Graph<indexer::Sum<A::I, B::I>>,
+}
+
+impl<A, B> Sum<A, B>
+where
+	A: Board,
+	B: Board,
+{
+	/// Put two boards side by side.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Rect, Sum};
+	/// # use gorrosion::core::util::indexer::Either::{Left, Right};
+	/// let sum = Sum::new(Rect::new(2, 2), Rect::new(1, 3));
+	/// let adj = sum.adjacencies();
+	/// assert!(adj[(Left((0, 0)), Left((0, 1)))]);
+	/// assert!(adj[(Right((0, 0)), Right((0, 1)))]);
+	/// assert!(!adj[(Left((0, 0)), Right((0, 0)))]);
+	/// ```
+	pub fn new(left: A, right: B) -> Sum<A, B> {
+		let adj = {
+			let left = left.adjacencies().to_dense();
+			let right = right.adjacencies().to_dense();
+			left.block_diag(&right)
+		};
+		let indexer = PhantomData;
+		let graph = Graph { adj, indexer };
+		Sum { left, right, graph }
+	}
+
+	/// The left part of the board.
+	pub fn left(&self) -> &A {
+		&self.left
+	}
+
+	/// The right part of the board.
+	pub fn right(&self) -> &B {
+		&self.right
+	}
+}
+
+impl<A, B> Board for Sum<A, B>
+where
+	A: Board,
+	B: Board,
+{
+	type I = indexer::Sum<A::I, B::I>;
+	type Adj = BoolMat<Self::I, Self::I>;
+
+	fn adjacencies(&self) -> &Self::Adj {
+		self.graph.adjacencies()
+	}
+
+	fn is_hoshi(&self, i: <Self::I as Indexer>::Index) -> bool {
+		match i {
+			Either::Left(a) => self.left.is_hoshi(a),
+			Either::Right(b) => self.right.is_hoshi(b),
+		}
+	}
+}
+
+/// The number of neighbours of every point, not counting itself,
+/// read off the rows of an adjacency matrix.
+/// The tests of the various boards share this.
+#[cfg(test)]
+fn degrees<I, A>(adj: &A) -> vec::Vec<usize, I>
+where
+	I: Indexer,
+	A: LinearMap<I, I>,
+{
+	let indexer = adj.rows();
+	let mut degrees = vec::Vec::zeros(indexer.clone());
+	for (a, b) in adj.entries() {
+		if indexer.to_num(a) != indexer.to_num(b) {
+			degrees[a] += 1;
+		}
+	}
+	degrees
+}
+
+// TODO: Implement the three standard boards with their hoshi
+// TODO: Boards need to provide more information about their hoshi
+//       than whether a given intersection point is one.
+
+// TODO: Throw out as soon as coverage tools can handle doctests.
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::util::bool_vec::BoolVec;
+
+	#[test]
+	fn doctests() {
+		let rect = Rect::new(6, 4);
+		// Get the adjacency matrix.
+		let adj = rect.adjacencies();
+		// Every intersection point can be reached from every intersection point
+		// in at most (6-1) + (4-1) = 8 steps.
+		let two_steps = adj * adj;
+		let four_steps = &two_steps * &two_steps;
+		// So, this should be the all-true matrix
+		let eight_steps = &four_steps * &four_steps;
+		assert!(eight_steps[((0, 0), (5, 3))]);
+	}
+
+	#[test]
+	fn sparse_graph() {
+		use core::util::sparse_mat::SparseBoolMat;
+		let rect = Rect::new(5, 3);
+		let adj = SparseBoolMat::from_dense(rect.adjacencies());
+		let indexer = PhantomData;
+		let graph = Graph { adj, indexer };
+		let adj = graph.adjacencies();
+		assert!(adj.entry((2, 1), (2, 2)));
+		assert!(!adj.entry((2, 1), (3, 2)));
+		assert_eq!(&adj.to_dense(), rect.adjacencies());
+	}
+
+	#[test]
+	fn graph_closure() {
+		// A triangle and a separate edge.
+		let mut adj = BoolMat::id_matrix(5);
+		for &(a, b) in &[(0, 1), (1, 2), (2, 0), (3, 4)] {
+			adj[(a, b)] = true;
+			adj[(b, a)] = true;
+		}
+		let indexer = PhantomData;
+		let graph = Graph { adj, indexer };
+		let reach = graph.adjacencies().closure();
+		assert_eq!(reach, graph.adjacencies().pow(2));
+		assert!(reach[(0, 2)]);
+		assert!(!reach[(0, 3)]);
+		let mut subset = BoolVec::trues(5);
+		subset[1] = false;
+		let reach = graph.adjacencies().closure_restricted(&subset);
+		assert!(reach[(0, 2)]);
+		assert!(reach[(4, 3)]);
+		assert!(!reach[(1, 1)]);
+	}
+
+	#[test]
+	fn sum() {
+		use core::util::indexer::Either::{Left, Right};
+		use core::util::vec::Vec;
+		let sum = Sum::new(Rect::new(2, 2), Rect::new(1, 3));
+		let adj = sum.adjacencies();
+		assert!(adj[(Left((0, 0)), Left((0, 1)))]);
+		assert!(adj[(Right((0, 0)), Right((0, 1)))]);
+		assert!(!adj[(Left((0, 0)), Right((0, 0)))]);
+		// The parts stay apart no matter how far we go.
+		let reach = adj.closure();
+		assert!(reach[(Left((0, 0)), Left((1, 1)))]);
+		assert!(reach[(Right((0, 0)), Right((0, 2)))]);
+		assert!(!reach[(Left((1, 1)), Right((0, 0)))]);
+		// Vectors on the parts combine to vectors on the sum.
+		let stones = BoolVec::trues(indexer::Rect::new(2, 2));
+		let none = BoolVec::falses(indexer::Rect::new(1, 3));
+		let all = Vec::direct_sum(&stones, &none);
+		let neighbours = adj.eval(&all);
+		assert_eq!(neighbours, all);
+		assert_eq!(neighbours.split(), (stones, none));
+	}
+
+	#[test]
+	fn graph_validation() {
+		let mut adj = BoolMat::id_matrix(3);
+		adj[(0, 1)] = true;
+		assert_eq!(
+			Graph::new(adj.clone()),
+			Err(GraphError::Asymmetric(0, 1))
+		);
+		adj[(1, 0)] = true;
+		assert!(Graph::new(adj.clone()).is_ok());
+		adj[(2, 2)] = false;
+		assert_eq!(Graph::new(adj), Err(GraphError::Irreflexive(2)));
+		let adj = BoolMat::trues(2, 3);
+		assert_eq!(Graph::new(adj), Err(GraphError::NonSquare));
+	}
+
+	#[test]
+	fn graph_builder() {
+		use core::util::sparse_mat::SparseBoolMat;
+		let rect = Rect::new(3, 4);
+		let indexer = indexer::Rect::new(3, 4);
+		let mut builder = Graph::builder(indexer);
+		for j in 0..3 {
+			for k in 0..4 {
+				if j > 0 {
+					builder = builder
+						.edge((j - 1, k), (j, k));
+				}
+				if k > 0 {
+					builder = builder
+						.edge((j, k - 1), (j, k));
+				}
+			}
+		}
+		let dense: Graph<_> = builder.clone().build().unwrap();
+		assert_eq!(dense.adjacencies(), rect.adjacencies());
+		let sparse: Graph<_, SparseBoolMat<_, _>> =
+			builder.build().unwrap();
+		assert_eq!(
+			&sparse.adjacencies().to_dense(),
+			rect.adjacencies()
+		);
+	}
+
+	#[test]
+	fn graph_builder_lists() {
+		use core::util::indexer::Labelled;
+		let labels = Labelled::new(vec!["hub", "a", "b"]);
+		let v = |l| labels.handle(l).unwrap();
+		let star: Result<Graph<_>, _> = Graph::builder(labels.clone())
+			.adjacency_list(v("hub"), vec![v("a"), v("b")])
+			.adjacency_list(v("a"), vec![v("hub")])
+			.adjacency_list(v("b"), vec![v("hub")])
+			.build();
+		let star = star.unwrap();
+		assert!(star.adjacencies()[(v("b"), v("hub"))]);
+		assert!(!star.adjacencies()[(v("b"), v("a"))]);
+		let lopsided: Result<Graph<_>, _> =
+			Graph::builder(labels.clone())
+				.adjacency_list(v("hub"), vec![v("a"), v("b")])
+				.adjacency_list(v("a"), vec![v("hub")])
+				.build();
+		assert_eq!(
+			lopsided,
+			Err(GraphError::Asymmetric(v("hub"), v("b")))
+		);
+	}
+
+	#[test]
+	fn graph_builder_invalid() {
+		let res: Result<Graph<_>, _> =
+			Graph::builder(5).edge(0, 1).edge(4, 5).build();
+		let err = res.unwrap_err();
+		assert_eq!(err, GraphError::InvalidVertex(5));
+		assert_eq!(err.to_string(), "invalid vertex 5");
+	}
+}
@@ -0,0 +1,535 @@
+//! Boards on the surfaces of polyhedra,
+//! with the intersection points being the vertices
+//! and the lines being the edges of the polyhedron.
+//! Most of them derive from the icosahedron:
+//! The dodecahedron is its dual,
+//! the truncated icosahedron (the classical football)
+//! cuts off its corners
+//! and geodesic spheres subdivide its faces.
+//!
+//! Star points mark those vertices which differ from the others,
+//! e.g. the corners of the cube.
+//! On polyhedra whose vertices all look alike
+//! there are no star points.
+
+use super::{Board, Cuboid, Graph};
+use core::util::bool_mat::BoolMat;
+use core::util::bool_vec::BoolVec;
+use core::util::indexer;
+use core::util::indexer::{Handle, Indexer, Labelled, Masked, Product};
+use core::util::linear_map::LinearMap;
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+/// The reflexive and symmetric adjacency matrix
+/// of the graph with the given edges.
+fn undirected<I: Indexer>(
+	indexer: I,
+	edges: &[(I::Index, I::Index)],
+) -> BoolMat<I, I> {
+	let loops = (0..indexer.range()).map(|n| indexer.to_index(n));
+	let loops = loops.map(|i| (i, i));
+	let reversed = edges.iter().map(|&(a, b)| (b, a));
+	let entries = edges.iter().cloned().chain(reversed).chain(loops);
+	let entries: Vec<_> = entries.collect();
+	BoolMat::from_entries(indexer.clone(), indexer, entries)
+}
+
+/// The adjacency matrix of the icosahedron,
+/// including the reflexive adjacencies.
+/// Vertex 0 is the north pole,
+/// 1 to 5 form the northern ring,
+/// 6 to 10 the southern ring
+/// and 11 is the south pole.
+fn icosahedron() -> BoolMat<usize, usize> {
+	let mut edges = Vec::new();
+	for k in 0..5 {
+		let (north, next_north) = (1 + k, 1 + (k + 1) % 5);
+		let (south, next_south) = (6 + k, 6 + (k + 1) % 5);
+		edges.push((0, north));
+		edges.push((north, next_north));
+		edges.push((north, south));
+		edges.push((north, next_south));
+		edges.push((south, next_south));
+		edges.push((south, 11));
+	}
+	undirected(12, &edges)
+}
+
+/// The twenty triangular faces of the icosahedron,
+/// each with its vertices in ascending order.
+fn icosahedron_faces(adj: &BoolMat<usize, usize>) -> Vec<[usize; 3]> {
+	let mut faces = Vec::new();
+	for a in 0..12 {
+		for b in a + 1..12 {
+			for c in b + 1..12 {
+				if adj[(a, b)] && adj[(b, c)] && adj[(a, c)] {
+					faces.push([a, b, c]);
+				}
+			}
+		}
+	}
+	faces
+}
+
+/// The surface of a cube,
+/// each face being a square grid with `side` lines in either direction.
+/// The points are those on the surface of a `Cuboid`,
+/// indexed as `(layer, row, column)`.
+/// The eight corners of the cube,
+/// where three faces meet and only three lines leave,
+/// are the star points.
+#[derive(PartialEq, Eq, Debug)]
+pub struct CubeSurface {
+	graph: Graph<Masked<indexer::Cuboid>>,
+}
+
+impl CubeSurface {
+	/// Create a new board on the surface of a cube
+	/// with `side` points along every edge, which has to be positive.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, CubeSurface};
+	/// # use gorrosion::core::util::indexer::Indexer;
+	/// let cube = CubeSurface::new(5);
+	/// let adj = cube.adjacencies();
+	/// assert_eq!(adj.rows().range(), 6 * 3 * 3 + 12 * 3 + 8);
+	/// // Across the edge between two faces.
+	/// assert!(adj[((0, 2, 0), (1, 2, 0))]);
+	/// assert!(!adj[((0, 2, 1), (1, 2, 0))]);
+	/// assert!(cube.is_hoshi((4, 0, 4)));
+	/// ```
+	pub fn new(side: usize) -> CubeSurface {
+		assert!(side > 0);
+		let solid = Cuboid::new(side, side, side);
+		let interior = |x: usize| 0 < x && x + 1 < side;
+		let indexer = solid.adjacencies().rows();
+		let mut surface = BoolVec::falses(indexer.clone());
+		for n in 0..indexer.range() {
+			let (x, y, z) = indexer.to_index(n);
+			surface[(x, y, z)] =
+				!(interior(x) && interior(y) && interior(z));
+		}
+		// Moving off the surface leads into the interior,
+		// so the surface carries the induced adjacencies.
+		let surface = Masked::new(&surface);
+		let adj =
+			solid.adjacencies().restrict(surface.clone(), surface);
+		let indexer = PhantomData;
+		let graph = Graph { adj, indexer };
+		CubeSurface { graph }
+	}
+}
+
+impl Board for CubeSurface {
+	type I = Masked<indexer::Cuboid>;
+	type Adj = BoolMat<Self::I, Self::I>;
+
+	fn adjacencies(&self) -> &Self::Adj {
+		self.graph.adjacencies()
+	}
+
+	fn is_hoshi(&self, i: <Self::I as Indexer>::Index) -> bool {
+		let last = self.graph.adjacencies().rows().parent().depth() - 1;
+		let corner = |x: usize| x == 0 || x == last;
+		corner(i.0) && corner(i.1) && corner(i.2)
+	}
+}
+
+/// The dodecahedron, with twenty vertices and three edges at each.
+/// The vertices `0` to `9` form a ring around the equator,
+/// alternately belonging to the northern and southern half.
+/// The vertex `10 + k` is adjacent to vertex `k`
+/// and to the vertices `10 + k ± 2` (modulo ten),
+/// so they form two pentagons around the poles.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Dodecahedron {
+	graph: Graph<usize>,
+}
+
+impl Dodecahedron {
+	/// Create a new board on a dodecahedron.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Dodecahedron};
+	/// let dodecahedron = Dodecahedron::new();
+	/// let adj = dodecahedron.adjacencies();
+	/// assert!(adj[(0, 9)]);
+	/// assert!(adj[(0, 10)]);
+	/// assert!(adj[(10, 18)]);
+	/// assert!(!adj[(10, 11)]);
+	/// ```
+	pub fn new() -> Dodecahedron {
+		let mut edges = Vec::new();
+		for k in 0..10 {
+			edges.push((k, (k + 1) % 10));
+			edges.push((k, 10 + k));
+			edges.push((10 + k, 10 + (k + 2) % 10));
+		}
+		let adj = undirected(20, &edges);
+		let indexer = PhantomData;
+		let graph = Graph { adj, indexer };
+		Dodecahedron { graph }
+	}
+}
+
+impl Default for Dodecahedron {
+	fn default() -> Self {
+		Dodecahedron::new()
+	}
+}
+
+impl Board for Dodecahedron {
+	type I = usize;
+	type Adj = BoolMat<Self::I, Self::I>;
+
+	fn adjacencies(&self) -> &Self::Adj {
+		self.graph.adjacencies()
+	}
+
+	fn is_hoshi(&self, _i: usize) -> bool {
+		false
+	}
+}
+
+/// The truncated icosahedron, i.e. the football,
+/// with sixty vertices, twelve pentagons and twenty hexagons.
+/// Cutting off a corner `a` of the icosahedron
+/// leaves a pentagon whose vertices lie
+/// on the edges from `a` to its neighbours.
+/// The vertex `(a, b)` is the one on the edge towards `b`.
+/// Besides its two neighbours on that pentagon,
+/// it is adjacent to `(b, a)` on the other end of the edge.
+/// The icosahedron's corners are numbered as follows:
+/// `0` is the north pole,
+/// `1` to `5` form the northern ring,
+/// `6` to `10` the southern ring
+/// and `11` is the south pole.
+#[derive(PartialEq, Eq, Debug)]
+pub struct TruncatedIcosahedron {
+	graph: Graph<Masked<Product<usize, usize>>>,
+}
+
+impl TruncatedIcosahedron {
+	/// Create a new board on a truncated icosahedron.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, TruncatedIcosahedron};
+	/// let football = TruncatedIcosahedron::new();
+	/// let adj = football.adjacencies();
+	/// assert!(adj[((0, 1), (1, 0))]);
+	/// assert!(adj[((0, 1), (0, 2))]);
+	/// assert!(!adj[((0, 1), (0, 3))]);
+	/// ```
+	pub fn new() -> TruncatedIcosahedron {
+		let ico = icosahedron();
+		let mut arcs = BoolVec::falses(Product::new(12, 12));
+		for a in 0..12 {
+			for b in 0..12 {
+				arcs[(a, b)] = a != b && ico[(a, b)];
+			}
+		}
+		let indexer = Masked::new(&arcs);
+		let mut edges = Vec::new();
+		for a in 0..12 {
+			for b in 0..12 {
+				if !arcs[(a, b)] {
+					continue;
+				}
+				if a < b {
+					edges.push(((a, b), (b, a)));
+				}
+				for c in b + 1..12 {
+					if arcs[(a, c)] && ico[(b, c)] {
+						edges.push(((a, b), (a, c)));
+					}
+				}
+			}
+		}
+		let adj = undirected(indexer, &edges);
+		let indexer = PhantomData;
+		let graph = Graph { adj, indexer };
+		TruncatedIcosahedron { graph }
+	}
+}
+
+impl Default for TruncatedIcosahedron {
+	fn default() -> Self {
+		TruncatedIcosahedron::new()
+	}
+}
+
+impl Board for TruncatedIcosahedron {
+	type I = Masked<Product<usize, usize>>;
+	type Adj = BoolMat<Self::I, Self::I>;
+
+	fn adjacencies(&self) -> &Self::Adj {
+		self.graph.adjacencies()
+	}
+
+	fn is_hoshi(&self, _i: (usize, usize)) -> bool {
+		false
+	}
+}
+
+/// A geodesic sphere:
+/// Each face of the icosahedron is divided into `frequency²` triangles,
+/// yielding `10 frequency² + 2` vertices.
+/// A vertex is labelled by the corners of the icosahedron
+/// of which it is a weighted average
+/// together with their integral, positive weights,
+/// which add up to the frequency.
+/// The labels are sorted by corner,
+/// with the corners numbered as for `TruncatedIcosahedron`.
+/// The twelve corners of the icosahedron,
+/// the only vertices with five instead of six neighbours,
+/// are the star points.
+#[derive(PartialEq, Eq, Debug)]
+pub struct GeodesicSphere {
+	graph: Graph<Labelled<Vec<(usize, usize)>>>,
+}
+
+impl GeodesicSphere {
+	/// Create a new geodesic sphere of the given positive frequency.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, GeodesicSphere};
+	/// # use gorrosion::core::util::indexer::Indexer;
+	/// let sphere = GeodesicSphere::new(3);
+	/// let adj = sphere.adjacencies();
+	/// let v = |label: &[(usize, usize)]| adj.rows().handle(label).unwrap();
+	/// assert_eq!(adj.rows().range(), 92);
+	/// assert!(adj[(v(&[(0, 3)]), v(&[(0, 2), (1, 1)]))]);
+	/// assert!(adj[(v(&[(0, 1), (1, 1), (2, 1)]), v(&[(0, 1), (1, 2)]))]);
+	/// assert!(sphere.is_hoshi(v(&[(0, 3)])));
+	/// ```
+	pub fn new(frequency: usize) -> GeodesicSphere {
+		assert!(frequency > 0);
+		let ico = icosahedron();
+		let faces = icosahedron_faces(&ico);
+		let label = |face: &[usize; 3], weights: [usize; 3]| {
+			let mut label: Vec<_> = face
+				.iter()
+				.cloned()
+				.zip(weights.iter().cloned())
+				.filter(|&(_, w)| w > 0)
+				.collect();
+			label.sort();
+			label
+		};
+		let points = |face| {
+			(0..=frequency).flat_map(move |i| {
+				(0..=frequency - i).map(move |j| {
+					(face, [i, j, frequency - i - j])
+				})
+			})
+		};
+		let mut labels = Vec::new();
+		let mut seen = HashSet::new();
+		for face in &faces {
+			for (face, weights) in points(face) {
+				let label = label(face, weights);
+				if seen.insert(label.clone()) {
+					labels.push(label);
+				}
+			}
+		}
+		let indexer = Labelled::new(labels);
+		let vertex = |face, weights| -> Handle {
+			indexer.handle(&label(face, weights)).unwrap()
+		};
+		let mut edges = Vec::new();
+		for face in &faces {
+			for (face, [i, j, k]) in points(face) {
+				let here = vertex(face, [i, j, k]);
+				if i > 0 {
+					edges.push((
+						here,
+						vertex(face, [i - 1, j + 1, k]),
+					));
+					edges.push((
+						here,
+						vertex(face, [i - 1, j, k + 1]),
+					));
+				}
+				if j > 0 {
+					edges.push((
+						here,
+						vertex(face, [i, j - 1, k + 1]),
+					));
+				}
+			}
+		}
+		let adj = undirected(indexer.clone(), &edges);
+		let indexer = PhantomData;
+		let graph = Graph { adj, indexer };
+		GeodesicSphere { graph }
+	}
+}
+
+impl Board for GeodesicSphere {
+	type I = Labelled<Vec<(usize, usize)>>;
+	type Adj = BoolMat<Self::I, Self::I>;
+
+	fn adjacencies(&self) -> &Self::Adj {
+		self.graph.adjacencies()
+	}
+
+	fn is_hoshi(&self, i: Handle) -> bool {
+		self.graph.adjacencies().rows().label(i).len() == 1
+	}
+}
+
+// TODO: Throw out as soon as coverage tools can handle doctests.
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::board::degrees;
+
+	/// The largest distance between two vertices.
+	fn diameter<I: Indexer>(adj: &BoolMat<I, I>) -> usize {
+		let all = BoolMat::trues(
+			adj.rows().clone(),
+			adj.columns().clone(),
+		);
+		(0..).find(|&n| adj.pow(n) == all).unwrap()
+	}
+
+	#[test]
+	fn doctests() {
+		let cube = CubeSurface::new(5);
+		let adj = cube.adjacencies();
+		assert_eq!(adj.rows().range(), 6 * 3 * 3 + 12 * 3 + 8);
+		assert!(adj[((0, 2, 0), (1, 2, 0))]);
+		assert!(!adj[((0, 2, 1), (1, 2, 0))]);
+		assert!(cube.is_hoshi((4, 0, 4)));
+
+		let dodecahedron = Dodecahedron::new();
+		let adj = dodecahedron.adjacencies();
+		assert!(adj[(0, 9)]);
+		assert!(adj[(0, 10)]);
+		assert!(adj[(10, 18)]);
+		assert!(!adj[(10, 11)]);
+
+		let football = TruncatedIcosahedron::new();
+		let adj = football.adjacencies();
+		assert!(adj[((0, 1), (1, 0))]);
+		assert!(adj[((0, 1), (0, 2))]);
+		assert!(!adj[((0, 1), (0, 3))]);
+
+		let sphere = GeodesicSphere::new(3);
+		let adj = sphere.adjacencies();
+		let v = |label: &[(usize, usize)]| {
+			adj.rows().handle(label).unwrap()
+		};
+		assert_eq!(adj.rows().range(), 92);
+		assert!(adj[(v(&[(0, 3)]), v(&[(0, 2), (1, 1)]))]);
+		assert!(sphere.is_hoshi(v(&[(0, 3)])));
+	}
+
+	#[test]
+	fn icosahedron_is_platonic() {
+		let ico = icosahedron();
+		assert_eq!(&ico.transpose(), &ico);
+		assert_eq!(degrees(&ico).as_slice(), &[5; 12]);
+		assert_eq!(icosahedron_faces(&ico).len(), 20);
+		assert_eq!(diameter(&ico), 3);
+	}
+
+	#[test]
+	fn cube_surface() {
+		for side in 2..6 {
+			let cube = CubeSurface::new(side);
+			let adj = cube.adjacencies();
+			let degrees = degrees(adj);
+			let degrees = degrees.as_slice();
+			let edges: usize = degrees.iter().sum::<usize>() / 2;
+			assert_eq!(
+				degrees.len(),
+				6 * side * side - 12 * side + 8
+			);
+			assert_eq!(edges, 12 * (side - 1) * (side - 1));
+			let indexer = adj.rows();
+			for (n, &degree) in degrees.iter().enumerate() {
+				let i = indexer.to_index(n);
+				assert_eq!(cube.is_hoshi(i), degree == 3);
+			}
+			assert_eq!(diameter(adj), 3 * (side - 1));
+		}
+	}
+
+	#[test]
+	fn dodecahedron() {
+		let adj = Dodecahedron::new().adjacencies().clone();
+		assert_eq!(&adj.transpose(), &adj);
+		assert_eq!(degrees(&adj).as_slice(), &[3; 20]);
+		assert_eq!(diameter(&adj), 5);
+	}
+
+	#[test]
+	fn football() {
+		let football = TruncatedIcosahedron::new();
+		let adj = football.adjacencies();
+		assert_eq!(&adj.transpose(), adj);
+		assert_eq!(degrees(adj).as_slice(), &[3; 60]);
+		assert_eq!(diameter(adj), 9);
+		// The vertices around a corner of the icosahedron form a pentagon.
+		let mut pentagon = BoolVec::falses(adj.rows().clone());
+		for b in 1..6 {
+			pentagon[(0, b)] = true;
+		}
+		let reach = adj.closure_restricted(&pentagon);
+		for b in 1..6 {
+			assert!(reach[((0, 1), (0, b))]);
+			let within = (1..6).filter(|&c| c != b);
+			let within = within.filter(|&c| adj[((0, b), (0, c))]);
+			assert_eq!(within.count(), 2);
+		}
+	}
+
+	#[test]
+	fn geodesic_sphere() {
+		for frequency in 1..5 {
+			let sphere = GeodesicSphere::new(frequency);
+			let adj = sphere.adjacencies();
+			assert_eq!(&adj.transpose(), adj);
+			let degrees = degrees(adj);
+			let degrees = degrees.as_slice();
+			let edges: usize = degrees.iter().sum::<usize>() / 2;
+			let f2 = frequency * frequency;
+			assert_eq!(degrees.len(), 10 * f2 + 2);
+			assert_eq!(edges, 30 * f2);
+			let indexer = adj.rows();
+			for (n, &degree) in degrees.iter().enumerate() {
+				let i = indexer.to_index(n);
+				assert_eq!(sphere.is_hoshi(i), degree == 5);
+				assert!(degree == 5 || degree == 6);
+			}
+			assert_eq!(diameter(adj), 3 * frequency);
+		}
+	}
+
+	#[test]
+	fn symmetries() {
+		// The full symmetry groups, including reflections.
+		assert_eq!(CubeSurface::new(3).automorphisms().len(), 48);
+		assert_eq!(Dodecahedron::default().automorphisms().len(), 120);
+		let football = TruncatedIcosahedron::default();
+		assert_eq!(football.automorphisms().len(), 120);
+		assert_eq!(GeodesicSphere::new(2).automorphisms().len(), 120);
+	}
+
+	#[test]
+	#[should_panic]
+	fn empty_cube() {
+		CubeSurface::new(0);
+	} // LCOV_EXCL_LINE
+}
@@ -0,0 +1,241 @@
+//! Local fights and life and death problems
+//! only ever involve part of the board.
+//! Cutting that part out makes for a smaller board to analyse,
+//! whose results can be carried back to the whole board.
+
+use super::{Board, Connectivity};
+use core::util::bool_mat::BoolMat;
+use core::util::bool_vec::BoolVec;
+use core::util::indexer::{Indexer, Masked};
+use core::util::linear_map::LinearMap;
+use core::util::semiring::Semiring;
+use core::util::vec::Vec;
+
+/// The sub-board induced by a region of another board:
+/// its points are those of the region,
+/// adjacent exactly if they are adjacent on the whole board.
+/// Indices are shared with the whole board,
+/// so every point of the region keeps its name.
+/// Cut out of a directed board, the sub-board is directed as well,
+/// with stones connecting the same way.
+///
+/// # Examples
+///
+/// ```
+/// # use gorrosion::core::board::{Board, Square, SubBoard};
+/// # use gorrosion::core::util::bool_vec::BoolVec;
+/// # use gorrosion::core::util::indexer::{self, Indexer};
+/// // The upper left corner of a large board.
+/// let square = Square::new(19);
+/// let mut region = BoolVec::falses(indexer::Rect::new(19, 19));
+/// for j in 0..4 {
+///	for k in 0..5 {
+///		region[(j, k)] = true;
+///	}
+/// }
+/// let corner = SubBoard::new(&square, &region);
+/// assert_eq!(corner.adjacencies().rows().range(), 20);
+/// assert!(corner.adjacencies()[((3, 4), (2, 4))]);
+/// // Stones carry over in both directions.
+/// let mut stones = BoolVec::falses(indexer::Rect::new(19, 19));
+/// stones[(1, 1)] = true;
+/// stones[(10, 10)] = true;
+/// let local = corner.project(&stones);
+/// assert_eq!(local.count(), 1);
+/// assert!(corner.lift(&local)[(1, 1)]);
+/// assert!(!corner.lift(&local)[(10, 10)]);
+/// ```
+#[derive(PartialEq, Eq, Debug)]
+pub struct SubBoard<I: Indexer> {
+	adj: BoolMat<Masked<I>, Masked<I>>,
+	hoshi: BoolVec<Masked<I>>,
+	connectivity: Connectivity,
+}
+
+impl<I> SubBoard<I>
+where
+	I: Indexer,
+{
+	/// Cut a region out of a board.
+	/// Star points of the board remain star points.
+	pub fn new<B>(board: &B, region: &BoolVec<I>) -> SubBoard<I>
+	where
+		B: Board<I = I>,
+	{
+		let diag = &BoolMat::from_diag(region);
+		// We restrict ourselves to the adjacencies within the region,
+		// just like the rules do for the stones of a player,
+		// and then drop the rows and columns outside of it.
+		let adj = &board.adjacencies().mul_left(diag) * diag;
+		let masked = Masked::new(region);
+		let adj = adj.restrict(masked.clone(), masked.clone());
+		let hoshi = (0..masked.range())
+			.map(|n| board.is_hoshi(masked.to_index(n)))
+			.collect();
+		let hoshi = BoolVec::from_data(hoshi, masked);
+		let connectivity = board.connectivity();
+		SubBoard {
+			adj,
+			hoshi,
+			connectivity,
+		}
+	}
+
+	/// The indexer of the region within the whole board.
+	pub fn region(&self) -> &Masked<I> {
+		self.adj.rows()
+	}
+
+	/// Restrict a vector on the whole board to the region,
+	/// e.g. to obtain the local position.
+	pub fn project<S>(&self, v: &Vec<S, I>) -> Vec<S, Masked<I>>
+	where
+		S: Semiring,
+	{
+		let region = self.region();
+		assert_eq!(region.parent(), v.indexer());
+		let data = (0..region.range())
+			.map(|n| v[region.to_index(n)])
+			.collect();
+		Vec::from_data(data, region.clone())
+	}
+
+	/// Extend a vector on the region to the whole board
+	/// by zero, e.g. by empty points.
+	pub fn lift<S>(&self, v: &Vec<S, Masked<I>>) -> Vec<S, I>
+	where
+		S: Semiring,
+	{
+		let region = self.region();
+		assert_eq!(region, v.indexer());
+		let mut lifted = Vec::zeros(region.parent().clone());
+		for n in 0..region.range() {
+			let i = region.to_index(n);
+			lifted[i] = v[i];
+		}
+		lifted
+	}
+}
+
+impl<I> Board for SubBoard<I>
+where
+	I: Indexer,
+{
+	type I = Masked<I>;
+	type Adj = BoolMat<Self::I, Self::I>;
+
+	fn adjacencies(&self) -> &Self::Adj {
+		&self.adj
+	}
+
+	fn is_hoshi(&self, i: <Self::I as Indexer>::Index) -> bool {
+		self.hoshi[i]
+	}
+
+	fn connectivity(&self) -> Connectivity {
+		self.connectivity
+	}
+}
+
+// TODO: Throw out as soon as coverage tools can handle doctests.
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::board::{CubeSurface, Digraph, Graph, Square};
+	use core::util::indexer;
+
+	#[test]
+	fn doctests() {
+		let square = Square::new(19);
+		let mut region = BoolVec::falses(indexer::Rect::new(19, 19));
+		for j in 0..4 {
+			for k in 0..5 {
+				region[(j, k)] = true;
+			}
+		}
+		let corner = SubBoard::new(&square, &region);
+		assert_eq!(corner.adjacencies().rows().range(), 20);
+		assert!(corner.adjacencies()[((3, 4), (2, 4))]);
+		let mut stones = BoolVec::falses(indexer::Rect::new(19, 19));
+		stones[(1, 1)] = true;
+		stones[(10, 10)] = true;
+		let local = corner.project(&stones);
+		assert_eq!(local.count(), 1);
+		assert!(corner.lift(&local)[(1, 1)]);
+		assert!(!corner.lift(&local)[(10, 10)]);
+	}
+
+	#[test]
+	fn induced_adjacencies() {
+		// A diagonal line cuts every edge.
+		let square = Square::new(4);
+		let mut region = BoolVec::falses(indexer::Rect::new(4, 4));
+		for j in 0..4 {
+			region[(j, j)] = true;
+		}
+		let diagonal = SubBoard::new(&square, &region);
+		let adj = diagonal.adjacencies();
+		assert_eq!(adj, &BoolMat::id_matrix(adj.rows().clone()));
+		// The sub-board of everything is the board itself.
+		let rect = indexer::Rect::new(4, 4);
+		let everything =
+			SubBoard::new(&square, &BoolVec::trues(rect.clone()));
+		let adj = everything.adjacencies().clone();
+		assert_eq!(
+			&adj.reindex(rect.clone(), rect),
+			square.adjacencies()
+		);
+	}
+
+	#[test]
+	fn directed() {
+		// A one-way street 0 → 1 → 2 → 3, cut short.
+		let street: Digraph<_> = Graph::builder(4)
+			.arc(0, 1)
+			.arc(1, 2)
+			.arc(2, 3)
+			.build_directed(Connectivity::Weak)
+			.unwrap();
+		let mut region = BoolVec::falses(4);
+		for n in 0..3 {
+			region[n] = true;
+		}
+		let short = SubBoard::new(&street, &region);
+		assert!(!short.is_symmetric());
+		assert_eq!(short.connectivity(), Connectivity::Weak);
+		assert!(short.adjacencies()[(0, 1)]);
+		assert!(!short.adjacencies()[(1, 0)]);
+		// The stones connect as they would on the whole street.
+		let stones = BoolVec::trues(short.region().clone());
+		assert!(short.connections(&stones)[(2, 0)]);
+	}
+
+	#[test]
+	fn round_trip() {
+		let cube = CubeSurface::new(3);
+		let surface = cube.adjacencies().rows().clone();
+		// One face of the cube.
+		let mut region = BoolVec::falses(surface.clone());
+		for j in 0..3 {
+			for k in 0..3 {
+				region[(0, j, k)] = true;
+			}
+		}
+		let face = SubBoard::new(&cube, &region);
+		assert_eq!(face.region().range(), 9);
+		assert!(face.is_hoshi((0, 0, 0)));
+		assert!(!face.is_hoshi((0, 1, 0)));
+		let counts = Vec::from_data(
+			(0..surface.range()).collect(),
+			surface.clone(),
+		);
+		let local = face.project(&counts);
+		assert_eq!(local[(0, 2, 2)], counts[(0, 2, 2)]);
+		let lifted = face.lift(&local);
+		for n in 0..surface.range() {
+			let i = surface.to_index(n);
+			let expected = if region[i] { counts[i] } else { 0 };
+			assert_eq!(lifted[i], expected);
+		}
+	}
+}
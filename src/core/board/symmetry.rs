@@ -0,0 +1,480 @@
+//! Finding the symmetries of arbitrary boards.
+//! Points are coloured by what distinguishes them,
+//! starting with their degree,
+//! and the colouring is refined until it is stable.
+//! Backtracking then fixes the image of one point after the other,
+//! refining again after each choice,
+//! and discards every choice which makes the colourings diverge.
+//! Every discrete colouring reached this way yields
+//! a candidate permutation, which is checked against the adjacencies.
+//!
+//! Once the symmetries are known,
+//! positions differing only by a symmetry
+//! can be brought into the same canonical form.
+
+use super::Board;
+use core::util::bool_vec::BoolVec;
+use core::util::indexer::Indexer;
+use core::util::linear_map::LinearMap;
+use core::util::permutation::Permutation;
+
+/// What a round of refinement saw:
+/// every distinct signature of colour and neighbour colours,
+/// in order, together with how many points have it.
+type Round = Vec<((usize, Vec<usize>), usize)>;
+
+/// The neighbours of every point, excluding itself, in ascending order.
+fn neighbours<I, A>(adj: &A) -> Vec<Vec<usize>>
+where
+	I: Indexer,
+	A: LinearMap<I, I>,
+{
+	let indexer = adj.rows();
+	let mut neighbours = vec![Vec::new(); indexer.range()];
+	for (a, b) in adj.entries() {
+		let (j, k) = (indexer.to_num(a), indexer.to_num(b));
+		if j != k {
+			neighbours[j].push(k);
+		}
+	}
+	for list in &mut neighbours {
+		list.sort();
+	}
+	neighbours
+}
+
+/// Refine a colouring until it is stable,
+/// i.e. until points of the same colour
+/// have equally many neighbours of every colour.
+/// The new colours only depend on the signatures,
+/// so isomorphic colourings are refined alike.
+/// The trace tells whether two refinements went the same way.
+fn refine(neighbours: &[Vec<usize>], colours: &mut [usize]) -> Vec<Round> {
+	let mut trace = Vec::new();
+	let mut count = {
+		let mut distinct = colours.to_vec();
+		distinct.sort();
+		distinct.dedup();
+		distinct.len()
+	};
+	loop {
+		let signatures: Vec<_> = neighbours
+			.iter()
+			.zip(colours.iter())
+			.map(|(list, &c)| {
+				let mut around: Vec<_> = list
+					.iter()
+					.map(|&k| colours[k])
+					.collect();
+				around.sort();
+				(c, around)
+			})
+			.collect();
+		let mut sorted = signatures.clone();
+		sorted.sort();
+		let mut round: Round = Vec::new();
+		for signature in sorted {
+			match round.last_mut() {
+				Some((last, n)) if *last == signature => {
+					*n += 1
+				}
+				_ => round.push((signature, 1)),
+			}
+		}
+		for (colour, signature) in colours.iter_mut().zip(&signatures) {
+			*colour = round
+				.binary_search_by(|(s, _)| s.cmp(signature))
+				.expect("every signature was recorded");
+		}
+		let split = round.len() > count;
+		count = round.len();
+		trace.push(round);
+		if !split {
+			return trace;
+		}
+	}
+}
+
+/// Give a point a colour of its own.
+fn individualise(colours: &[usize], point: usize) -> Vec<usize> {
+	colours.iter()
+		.enumerate()
+		.map(|(n, &c)| 2 * c + if n == point { 0 } else { 1 })
+		.collect()
+}
+
+/// Extend the correspondence between two equally refined colourings
+/// in every possible way and collect the automorphisms found.
+fn search(
+	neighbours: &[Vec<usize>],
+	domain: &[usize],
+	image: &[usize],
+	found: &mut Vec<Vec<usize>>,
+) {
+	let size = domain.len();
+	let mut sizes = vec![0; size];
+	for &c in domain {
+		sizes[c] += 1;
+	}
+	match (0..size).find(|&n| sizes[domain[n]] > 1) {
+		None => {
+			// Both colourings are discrete,
+			// so the colours pair the points off.
+			let mut by_colour = vec![0; size];
+			for (m, &c) in image.iter().enumerate() {
+				by_colour[c] = m;
+			}
+			let images: Vec<_> =
+				domain.iter().map(|&c| by_colour[c]).collect();
+			let preserves = |(j, list): (usize, &Vec<usize>)| {
+				let around = &neighbours[images[j]];
+				list.iter().all(|&k| {
+					around.binary_search(&images[k]).is_ok()
+				})
+			};
+			if neighbours.iter().enumerate().all(preserves) {
+				found.push(images);
+			}
+		}
+		Some(point) => {
+			let mut fixed = individualise(domain, point);
+			let trace = refine(neighbours, &mut fixed);
+			for m in
+				(0..size).filter(|&m| image[m] == domain[point])
+			{
+				let mut moved = individualise(image, m);
+				if refine(neighbours, &mut moved) == trace {
+					search(
+						neighbours, &fixed, &moved,
+						found,
+					);
+				}
+			}
+		}
+	}
+}
+
+/// All permutations of the points preserving the adjacencies,
+/// starting with the identity.
+pub(super) fn automorphisms<I, A>(adj: &A) -> Vec<Permutation<I>>
+where
+	I: Indexer,
+	A: LinearMap<I, I>,
+{
+	let neighbours = neighbours(adj);
+	let mut colours = vec![0; neighbours.len()];
+	refine(&neighbours, &mut colours);
+	let mut found = Vec::new();
+	search(&neighbours, &colours, &colours, &mut found);
+	let indexer = adj.rows();
+	found.into_iter()
+		.map(|images| Permutation::from_images(indexer.clone(), images))
+		.collect()
+}
+
+/// The symmetries of a board,
+/// computed once to compare any number of positions on it.
+///
+/// # Examples
+///
+/// ```
+/// # use gorrosion::core::board::{Square, Symmetries};
+/// # use gorrosion::core::util::bool_vec::BoolVec;
+/// # use gorrosion::core::util::indexer;
+/// let symmetries = Symmetries::new(&Square::new(9));
+/// let empty = BoolVec::falses(indexer::Rect::new(9, 9));
+/// let mut stone = empty.clone();
+/// stone[(2, 3)] = true;
+/// let mut mirrored = empty.clone();
+/// mirrored[(6, 3)] = true;
+/// assert_eq!(
+///	symmetries.hash(&stone, &empty),
+///	symmetries.hash(&mirrored, &empty),
+/// );
+/// // Without colour swaps, colours keep mattering.
+/// assert_ne!(
+///	symmetries.hash(&stone, &empty),
+///	symmetries.hash(&empty, &mirrored),
+/// );
+/// let symmetries = symmetries.swap_colours(true);
+/// assert_eq!(
+///	symmetries.hash(&stone, &empty),
+///	symmetries.hash(&empty, &mirrored),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Symmetries<I: Indexer> {
+	group: Vec<Permutation<I>>,
+	swap_colours: bool,
+}
+
+/// A position in canonical form,
+/// together with the way it was reached from the original.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Canonical<I: Indexer> {
+	black: BoolVec<I>,
+	white: BoolVec<I>,
+	symmetry: Permutation<I>,
+	swapped: bool,
+}
+
+impl<I> Canonical<I>
+where
+	I: Indexer,
+{
+	/// The black stones of the canonical position.
+	pub fn black(&self) -> &BoolVec<I> {
+		&self.black
+	}
+
+	/// The white stones of the canonical position.
+	pub fn white(&self) -> &BoolVec<I> {
+		&self.white
+	}
+
+	/// The symmetry carrying the original position to the canonical one.
+	pub fn symmetry(&self) -> &Permutation<I> {
+		&self.symmetry
+	}
+
+	/// Whether the colours were swapped as well.
+	pub fn swapped(&self) -> bool {
+		self.swapped
+	}
+}
+
+/// Write a position down as a sequence of points,
+/// each being empty, black or white in this order.
+fn encode<I: Indexer>(black: &BoolVec<I>, white: &BoolVec<I>) -> Vec<u8> {
+	black.as_slice()
+		.iter()
+		.zip(white.as_slice())
+		.map(|(&b, &w)| match (b, w) {
+			(true, _) => 1,
+			(false, true) => 2,
+			(false, false) => 0,
+		})
+		.collect()
+}
+
+impl<I> Symmetries<I>
+where
+	I: Indexer,
+{
+	/// Find the symmetries of a board.
+	/// Colours are not swapped.
+	pub fn new<B>(board: &B) -> Self
+	where
+		B: Board<I = I>,
+	{
+		let group = board.automorphisms();
+		let swap_colours = false;
+		Symmetries {
+			group,
+			swap_colours,
+		}
+	}
+
+	/// Decide whether positions with swapped colours count as the same.
+	pub fn swap_colours(mut self, allowed: bool) -> Self {
+		self.swap_colours = allowed;
+		self
+	}
+
+	/// The symmetries, starting with the identity.
+	pub fn group(&self) -> &[Permutation<I>] {
+		&self.group
+	}
+
+	/// Pick the representative of a position which comes first
+	/// when points are compared in the order of the indexer,
+	/// empty points before black stones before white stones.
+	/// Among several symmetries leading there,
+	/// the first one in `group` is reported.
+	pub fn canonical(
+		&self,
+		black: &BoolVec<I>,
+		white: &BoolVec<I>,
+	) -> Canonical<I> {
+		let swaps: &[bool] = if self.swap_colours {
+			&[false, true]
+		} else {
+			&[false]
+		};
+		let mut best: Option<(Vec<u8>, Canonical<I>)> = None;
+		for &swapped in swaps {
+			let (black, white) = if swapped {
+				(white, black)
+			} else {
+				(black, white)
+			};
+			for symmetry in &self.group {
+				let black = symmetry.permute(black);
+				let white = symmetry.permute(white);
+				let code = encode(&black, &white);
+				if best.as_ref().is_none_or(|(b, _)| code < *b)
+				{
+					let symmetry = symmetry.clone();
+					let canonical = Canonical {
+						black,
+						white,
+						symmetry,
+						swapped,
+					};
+					best = Some((code, canonical));
+				}
+			}
+		}
+		best.expect("the group contains the identity").1
+	}
+
+	/// A hash of the position which agrees for all positions
+	/// with the same canonical form.
+	/// This is FNV-1a on the canonical form,
+	/// so it is stable across runs and platforms
+	/// and may be stored in a database.
+	pub fn hash(&self, black: &BoolVec<I>, white: &BoolVec<I>) -> u64 {
+		let canonical = self.canonical(black, white);
+		encode(&canonical.black, &canonical.white).iter().fold(
+			0xcbf2_9ce4_8422_2325,
+			|hash, &point| {
+				(hash ^ u64::from(point))
+					.wrapping_mul(0x100_0000_01b3)
+			},
+		)
+	}
+}
+
+// TODO: Throw out as soon as coverage tools can handle doctests.
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::board::{Graph, Rect, Square};
+	use core::util::bool_mat::BoolMat;
+	use core::util::indexer;
+	use std::collections::HashSet;
+
+	#[test]
+	fn doctests() {
+		let symmetries = Symmetries::new(&Square::new(9));
+		let empty = BoolVec::falses(indexer::Rect::new(9, 9));
+		let mut stone = empty.clone();
+		stone[(2, 3)] = true;
+		let mut mirrored = empty.clone();
+		mirrored[(6, 3)] = true;
+		assert_eq!(
+			symmetries.hash(&stone, &empty),
+			symmetries.hash(&mirrored, &empty),
+		);
+		assert_ne!(
+			symmetries.hash(&stone, &empty),
+			symmetries.hash(&empty, &mirrored),
+		);
+		let symmetries = symmetries.swap_colours(true);
+		assert_eq!(
+			symmetries.hash(&stone, &empty),
+			symmetries.hash(&empty, &mirrored),
+		);
+	}
+
+	#[test]
+	fn rect_symmetries() {
+		for &(height, width) in
+			&[(1, 1), (1, 2), (1, 4), (2, 2), (2, 3), (4, 4)]
+		{
+			let rect = Rect::new(height, width);
+			let listed = rect.automorphisms();
+			let searched = automorphisms(rect.adjacencies());
+			assert!(listed[0].is_identity());
+			assert!(searched[0].is_identity());
+			assert_eq!(listed.len(), searched.len());
+			assert!(listed.iter().all(|p| searched.contains(p)));
+			let adj = rect.adjacencies();
+			assert!(listed
+				.iter()
+				.all(|p| &p.conjugate(adj) == adj));
+		}
+		assert_eq!(Rect::new(3, 5).automorphisms().len(), 4);
+		assert_eq!(Square::new(19).automorphisms().len(), 8);
+		assert_eq!(Rect::new(1, 5).automorphisms().len(), 2);
+	}
+
+	#[test]
+	fn transform_positions() {
+		let square = Square::new(3);
+		let mut stones = BoolVec::falses(indexer::Rect::new(3, 3));
+		stones[(0, 1)] = true;
+		let images: HashSet<_> = square
+			.automorphisms()
+			.iter()
+			.map(|p| {
+				let moved = p.permute(&stones);
+				(0..9).find(|&n| moved.as_slice()[n]).unwrap()
+			})
+			.collect();
+		// A side point can go to any of the four side points.
+		assert_eq!(images, [1, 3, 5, 7].iter().cloned().collect());
+	}
+
+	#[test]
+	fn graph_symmetries() {
+		// A path with a pendant edge in the middle
+		// and a separate triangle.
+		let mut adj = BoolMat::id_matrix(7);
+		for &(a, b) in &[(0, 1), (1, 2), (1, 3), (4, 5), (5, 6), (6, 4)]
+		{
+			adj[(a, b)] = true;
+			adj[(b, a)] = true;
+		}
+		let graph = Graph::new(adj).unwrap();
+		let symmetries = graph.automorphisms();
+		assert_eq!(symmetries.len(), 6 * 6);
+		assert!(symmetries.iter().all(|p| p.image(1) == 1));
+		let adj = graph.adjacencies();
+		assert!(symmetries.iter().all(|p| &p.conjugate(adj) == adj));
+	}
+
+	#[test]
+	fn canonical_positions() {
+		let square = Square::new(4);
+		let rect = indexer::Rect::new(4, 4);
+		let symmetries = Symmetries::new(&square);
+		let mut black = BoolVec::falses(rect.clone());
+		let mut white = BoolVec::falses(rect.clone());
+		black[(1, 2)] = true;
+		white[(3, 3)] = true;
+		let canonical = symmetries.canonical(&black, &white);
+		assert!(!canonical.swapped());
+		assert_eq!(
+			&canonical.symmetry().permute(&black),
+			canonical.black()
+		);
+		// Empty points come first, so the stones move
+		// as far towards the end of the board as possible.
+		assert!(canonical.black()[(2, 2)]);
+		assert!(canonical.white()[(3, 0)]);
+		let hash = symmetries.hash(&black, &white);
+		for symmetry in symmetries.group() {
+			let (b, w) = (
+				symmetry.permute(&black),
+				symmetry.permute(&white),
+			);
+			assert_eq!(
+				symmetries.canonical(&b, &w).black(),
+				canonical.black()
+			);
+			assert_eq!(symmetries.hash(&b, &w), hash);
+		}
+		// Moving a stone elsewhere changes the position.
+		let mut moved = BoolVec::falses(rect);
+		moved[(1, 1)] = true;
+		assert_ne!(symmetries.hash(&moved, &white), hash);
+		// Swapping colours is optional.
+		assert_ne!(symmetries.hash(&white, &black), hash);
+		let symmetries = symmetries.swap_colours(true);
+		let swapped = symmetries.canonical(&white, &black);
+		assert!(swapped.swapped());
+		assert_eq!(swapped.black(), canonical.black());
+		assert_eq!(symmetries.hash(&white, &black), hash);
+	}
+}
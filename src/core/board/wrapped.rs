@@ -0,0 +1,495 @@
+//! Rectangular boards need not have edges:
+//! Gluing opposite edges of the rectangle together
+//! yields boards on surfaces like the torus.
+//! Since Go only cares about adjacencies,
+//! the rules work on them just as well.
+//!
+//! All of these boards use the coordinates of a `Rect`,
+//! `(j, k)` being the point in row `j` and column `k`.
+//! Their `Display` implementations draw the empty board
+//! and label the ends of the lines crossing a glued edge
+//! such that lines with the same label are joined.
+//!
+//! ```none
+//!    0 1 2
+//!    │ │ │
+//! 0 ─┼─┼─┼─ 1
+//! 1 ─┼─┼─┼─ 0
+//!    │ │ │
+//!    0 1 2
+//! ```
+
+use super::{cycle, junction, path, Board, Graph};
+use core::util::bool_mat::BoolMat;
+use core::util::indexer;
+use core::util::indexer::Indexer;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// How a pair of opposite edges of a rectangle is treated.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Gluing {
+	/// The edges stay edges.
+	Open,
+	/// The edges are glued together as they are.
+	Straight,
+	/// The edges are glued together after reflecting one of them.
+	Twisted,
+}
+
+impl Gluing {
+	/// The adjacency matrix of one line of the rectangle,
+	/// with the ends glued together if the edges are.
+	/// A twist is added separately by `glue`.
+	fn line(self, length: usize) -> BoolMat<usize, usize> {
+		match self {
+			Gluing::Straight => cycle(length),
+			Gluing::Open | Gluing::Twisted => path(length),
+		}
+	}
+
+	/// The line ending at the given position of one edge
+	/// continues at this position on the opposite edge.
+	fn partner(self, position: usize, length: usize) -> usize {
+		match self {
+			Gluing::Twisted => length - 1 - position,
+			Gluing::Open | Gluing::Straight => position,
+		}
+	}
+}
+
+/// Build the adjacency matrix of a rectangle whose
+/// left and right edges are glued according to `sides`
+/// and whose top and bottom edges are glued according to `ends`.
+fn glue(
+	height: usize,
+	width: usize,
+	sides: Gluing,
+	ends: Gluing,
+) -> Graph<indexer::Rect> {
+	let indexer = indexer::Rect::new(height, width);
+	let adj = ends.line(height).kronecker_sum(&sides.line(width));
+	let mut adj = adj.reindex(indexer.clone(), indexer);
+	if sides == Gluing::Twisted && width > 0 {
+		for j in 0..height {
+			let (a, b) = ((j, width - 1), (height - 1 - j, 0));
+			adj[(a, b)] = true;
+			adj[(b, a)] = true;
+		}
+	}
+	if ends == Gluing::Twisted && height > 0 {
+		for k in 0..width {
+			let (a, b) = ((height - 1, k), (0, width - 1 - k));
+			adj[(a, b)] = true;
+			adj[(b, a)] = true;
+		}
+	}
+	let indexer = PhantomData;
+	Graph { adj, indexer }
+}
+
+/// Draw an empty rectangle with glued edges as described in the module.
+fn render(
+	f: &mut fmt::Formatter,
+	rect: &indexer::Rect,
+	sides: Gluing,
+	ends: Gluing,
+) -> fmt::Result {
+	let (height, width) = (rect.height(), rect.width());
+	let digits = |n: usize| n.saturating_sub(1).to_string().len();
+	let row_label = digits(height);
+	// The columns need to be far enough apart for their labels.
+	let column_label = if ends == Gluing::Open {
+		1
+	} else {
+		digits(width)
+	};
+	let margin = if sides == Gluing::Open {
+		String::new()
+	} else {
+		" ".repeat(row_label + 2)
+	};
+	let spacer = " ".repeat(column_label);
+	let verticals = vec!["│"; width].join(&spacer);
+	let labels = |twisted: Gluing| {
+		let labels: Vec<_> = (0..width)
+			.map(|k| twisted.partner(k, width))
+			.map(|k| format!("{:<1$}", k, column_label))
+			.collect();
+		labels.join(" ")
+	};
+	let mut lines = Vec::new();
+	if ends != Gluing::Open {
+		lines.push(format!("{}{}", margin, labels(Gluing::Straight)));
+		lines.push(format!("{}{}", margin, verticals));
+	}
+	let glued_ends = ends != Gluing::Open;
+	let glued_sides = sides != Gluing::Open;
+	for j in 0..height {
+		let mut line = String::new();
+		if glued_sides {
+			line += &format!("{:>1$} ─", j, row_label);
+		}
+		for k in 0..width {
+			if k > 0 {
+				line += &"─".repeat(column_label);
+			}
+			line.push(junction(
+				j > 0 || glued_ends,
+				j + 1 < height || glued_ends,
+				k > 0 || glued_sides,
+				k + 1 < width || glued_sides,
+			));
+		}
+		if glued_sides {
+			line += &format!("─ {}", sides.partner(j, height));
+		}
+		lines.push(line);
+	}
+	if glued_ends {
+		lines.push(format!("{}{}", margin, verticals));
+		lines.push(format!("{}{}", margin, labels(ends)));
+	}
+	for line in lines {
+		writeln!(f, "{}", line.trim_end())?;
+	}
+	Ok(())
+}
+
+/// Generate the parts common to all rectangles with glued edges.
+macro_rules! glued_board {
+	($name:ident, $sides:expr, $ends:expr) => {
+		impl Board for $name {
+			type I = indexer::Rect;
+			type Adj = BoolMat<Self::I, Self::I>;
+
+			fn adjacencies(&self) -> &Self::Adj {
+				self.graph.adjacencies()
+			}
+
+			fn is_hoshi(
+				&self,
+				_i: <Self::I as Indexer>::Index,
+			) -> bool {
+				false
+			}
+		}
+
+		impl fmt::Display for $name {
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				let rect = self.graph.adjacencies().rows();
+				render(f, rect, $sides, $ends)
+			}
+		}
+	};
+}
+
+/// A rectangle with both pairs of opposite edges glued together,
+/// i.e. the surface of a doughnut.
+/// Every intersection point has exactly four neighbours,
+/// so there are neither edges nor corners.
+/// The coordinates are the same as on a `Rect`,
+/// with `(j, width - 1)` adjacent to `(j, 0)`
+/// and `(height - 1, k)` adjacent to `(0, k)`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Torus {
+	graph: Graph<indexer::Rect>,
+}
+
+impl Torus {
+	/// Create a new toroidal board with the given measurements.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Torus};
+	/// let torus = Torus::new(5, 7);
+	/// let adj = torus.adjacencies();
+	/// assert!(adj[((0, 0), (4, 0))]);
+	/// assert!(adj[((0, 0), (0, 6))]);
+	/// assert!(!adj[((0, 0), (4, 6))]);
+	/// ```
+	pub fn new(height: usize, width: usize) -> Torus {
+		// The torus is the Cartesian product of two cycles.
+		let graph =
+			glue(height, width, Gluing::Straight, Gluing::Straight);
+		Torus { graph }
+	}
+}
+
+glued_board!(Torus, Gluing::Straight, Gluing::Straight);
+
+/// A rectangle with its left and right edge glued together,
+/// i.e. the surface of a tube.
+/// The coordinates are the same as on a `Rect`,
+/// with `(j, width - 1)` adjacent to `(j, 0)`.
+/// The top and bottom edge remain edges.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Cylinder {
+	graph: Graph<indexer::Rect>,
+}
+
+impl Cylinder {
+	/// Create a new cylindrical board with the given measurements.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Cylinder};
+	/// let cylinder = Cylinder::new(5, 7);
+	/// let adj = cylinder.adjacencies();
+	/// assert!(adj[((2, 0), (2, 6))]);
+	/// assert!(!adj[((0, 0), (4, 0))]);
+	/// assert_eq!(
+	///	cylinder.to_string(),
+	///	"0 ─┬─┬─┬─┬─┬─┬─┬─ 0\n\
+	///	 1 ─┼─┼─┼─┼─┼─┼─┼─ 1\n\
+	///	 2 ─┼─┼─┼─┼─┼─┼─┼─ 2\n\
+	///	 3 ─┼─┼─┼─┼─┼─┼─┼─ 3\n\
+	///	 4 ─┴─┴─┴─┴─┴─┴─┴─ 4\n",
+	/// );
+	/// ```
+	pub fn new(height: usize, width: usize) -> Cylinder {
+		let graph = glue(height, width, Gluing::Straight, Gluing::Open);
+		Cylinder { graph }
+	}
+}
+
+glued_board!(Cylinder, Gluing::Straight, Gluing::Open);
+
+/// A rectangle with its left and right edge glued together
+/// after turning one of them upside down.
+/// The coordinates are the same as on a `Rect`,
+/// with `(j, width - 1)` adjacent to `(height - 1 - j, 0)`.
+/// The top and bottom edge remain edges,
+/// but they are joined to one single edge by the twist.
+#[derive(PartialEq, Eq, Debug)]
+pub struct MoebiusStrip {
+	graph: Graph<indexer::Rect>,
+}
+
+impl MoebiusStrip {
+	/// Create a new board on a Möbius strip with the given measurements.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, MoebiusStrip};
+	/// let strip = MoebiusStrip::new(3, 4);
+	/// let adj = strip.adjacencies();
+	/// assert!(adj[((0, 3), (2, 0))]);
+	/// assert!(adj[((1, 3), (1, 0))]);
+	/// assert!(!adj[((0, 3), (0, 0))]);
+	/// assert_eq!(
+	///	strip.to_string(),
+	///	"0 ─┬─┬─┬─┬─ 2\n\
+	///	 1 ─┼─┼─┼─┼─ 1\n\
+	///	 2 ─┴─┴─┴─┴─ 0\n",
+	/// );
+	/// ```
+	pub fn new(height: usize, width: usize) -> MoebiusStrip {
+		let graph = glue(height, width, Gluing::Twisted, Gluing::Open);
+		MoebiusStrip { graph }
+	}
+}
+
+glued_board!(MoebiusStrip, Gluing::Twisted, Gluing::Open);
+
+/// A rectangle with its top and bottom edge glued together
+/// as on a cylinder and its left and right edge glued together
+/// after turning one of them upside down as on a Möbius strip.
+/// The coordinates are the same as on a `Rect`,
+/// with `(height - 1, k)` adjacent to `(0, k)`
+/// and `(j, width - 1)` adjacent to `(height - 1 - j, 0)`.
+/// Like on the torus, every point has exactly four neighbours.
+#[derive(PartialEq, Eq, Debug)]
+pub struct KleinBottle {
+	graph: Graph<indexer::Rect>,
+}
+
+impl KleinBottle {
+	/// Create a new board on a Klein bottle with the given measurements.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, KleinBottle};
+	/// let bottle = KleinBottle::new(2, 3);
+	/// let adj = bottle.adjacencies();
+	/// assert!(adj[((0, 1), (1, 1))]);
+	/// assert!(adj[((0, 2), (1, 0))]);
+	/// assert_eq!(
+	///	bottle.to_string(),
+	///	"   0 1 2\n   \
+	///		│ │ │\n\
+	///	 0 ─┼─┼─┼─ 1\n\
+	///	 1 ─┼─┼─┼─ 0\n   \
+	///		│ │ │\n   \
+	///		0 1 2\n",
+	/// );
+	/// ```
+	pub fn new(height: usize, width: usize) -> KleinBottle {
+		let graph =
+			glue(height, width, Gluing::Twisted, Gluing::Straight);
+		KleinBottle { graph }
+	}
+}
+
+glued_board!(KleinBottle, Gluing::Twisted, Gluing::Straight);
+
+// TODO: Throw out as soon as coverage tools can handle doctests.
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::board::degrees;
+	use core::util::bool_vec::BoolVec;
+	use core::util::vec;
+
+	/// The number of connected components of the given points.
+	fn components<B: Board<Adj = BoolMat<indexer::Rect, indexer::Rect>>>(
+		board: &B,
+		points: &BoolVec<indexer::Rect>,
+	) -> usize {
+		let reach = board.adjacencies().closure_restricted(points);
+		let mut seen = BoolVec::falses(points.indexer().clone());
+		let mut count = 0;
+		let indexer = points.indexer();
+		for i in (0..indexer.range()).map(|n| indexer.to_index(n)) {
+			if points[i] && !seen[i] {
+				count += 1;
+				let mut single =
+					BoolVec::falses(seen.indexer().clone());
+				single[i] = true;
+				seen = &seen | &reach.eval(&single);
+			}
+		}
+		count
+	}
+
+	/// The points on the top and the bottom row.
+	fn top_and_bottom(
+		height: usize,
+		width: usize,
+	) -> BoolVec<indexer::Rect> {
+		let mut edge =
+			BoolVec::falses(indexer::Rect::new(height, width));
+		for k in 0..width {
+			edge[(0, k)] = true;
+			edge[(height - 1, k)] = true;
+		}
+		edge
+	}
+
+	#[test]
+	fn doctests() {
+		let torus = Torus::new(5, 7);
+		let adj = torus.adjacencies();
+		assert!(adj[((0, 0), (4, 0))]);
+		assert!(adj[((0, 0), (0, 6))]);
+		assert!(!adj[((0, 0), (4, 6))]);
+
+		let cylinder = Cylinder::new(5, 7);
+		let adj = cylinder.adjacencies();
+		assert!(adj[((2, 0), (2, 6))]);
+		assert!(!adj[((0, 0), (4, 0))]);
+
+		let strip = MoebiusStrip::new(3, 4);
+		let adj = strip.adjacencies();
+		assert!(adj[((0, 3), (2, 0))]);
+		assert!(adj[((1, 3), (1, 0))]);
+		assert!(!adj[((0, 3), (0, 0))]);
+		assert_eq!(
+			strip.to_string(),
+			"0 ─┬─┬─┬─┬─ 2\n1 ─┼─┼─┼─┼─ 1\n2 ─┴─┴─┴─┴─ 0\n",
+		);
+
+		let bottle = KleinBottle::new(2, 3);
+		let adj = bottle.adjacencies();
+		assert!(adj[((0, 1), (1, 1))]);
+		assert!(adj[((0, 2), (1, 0))]);
+	}
+
+	#[test]
+	fn torus_is_regular() {
+		let (height, width) = (4, 6);
+		let torus = Torus::new(height, width);
+		let adj = torus.adjacencies().map(|b| if b { 1 } else { 0 });
+		let ones = vec::Vec::ones(indexer::Rect::new(height, width));
+		let degrees = adj.eval(&ones);
+		// Four neighbours and the point itself.
+		assert_eq!(degrees, ones.map(|n: usize| 5 * n));
+		assert_eq!(adj.transpose(), adj);
+	}
+
+	#[test]
+	fn torus_distances() {
+		// On a torus, no point is further away from another
+		// than half the height plus half the width.
+		let torus = Torus::new(6, 8);
+		let adj = torus.adjacencies();
+		let all = BoolMat::trues(
+			adj.rows().clone(),
+			adj.columns().clone(),
+		);
+		assert_eq!(adj.pow(3 + 4), all);
+		assert!(adj.pow(3 + 3) != all);
+		let mut stone = BoolVec::falses(adj.rows().clone());
+		stone[(0, 0)] = true;
+		let reach = adj.pow(2).eval(&stone);
+		assert!(reach[(4, 0)]);
+		assert!(reach[(5, 7)]);
+		assert!(!reach[(3, 0)]);
+	}
+
+	#[test]
+	fn klein_bottle_is_regular() {
+		let bottle = KleinBottle::new(4, 5);
+		let adj = bottle.adjacencies();
+		let ones = vec::Vec::ones(adj.rows().clone());
+		assert_eq!(degrees(adj), ones.map(|n: usize| 4 * n));
+		assert_eq!(&adj.transpose(), adj);
+		assert!(*adj != *Torus::new(4, 5).adjacencies());
+	}
+
+	#[test]
+	fn strips_have_edges() {
+		let (height, width) = (4, 5);
+		let edge = top_and_bottom(height, width);
+		let cylinder = Cylinder::new(height, width);
+		let strip = MoebiusStrip::new(height, width);
+		// Only the points on the edge lack a neighbour.
+		let expected = edge.map(|b| if b { 3 } else { 4 });
+		assert_eq!(degrees(cylinder.adjacencies()), expected);
+		assert_eq!(degrees(strip.adjacencies()), expected);
+		assert_eq!(
+			&strip.adjacencies().transpose(),
+			strip.adjacencies()
+		);
+		// A cylinder has two edges, a Möbius strip only one.
+		assert_eq!(components(&cylinder, &edge), 2);
+		assert_eq!(components(&strip, &edge), 1);
+	}
+
+	#[test]
+	fn rendering() {
+		assert_eq!(
+			Torus::new(2, 3).to_string(),
+			"   0 1 2\n   │ │ │\n\
+			 0 ─┼─┼─┼─ 0\n1 ─┼─┼─┼─ 1\n   │ │ │\n   0 1 2\n",
+		);
+		assert_eq!(
+			Cylinder::new(3, 2).to_string(),
+			"0 ─┬─┬─ 0\n1 ─┼─┼─ 1\n2 ─┴─┴─ 2\n",
+		);
+		assert_eq!(
+			KleinBottle::new(2, 3).to_string(),
+			"   0 1 2\n   │ │ │\n\
+			 0 ─┼─┼─┼─ 1\n1 ─┼─┼─┼─ 0\n   │ │ │\n   0 1 2\n",
+		);
+		// Labels with more digits spread out the lines.
+		let torus = Torus::new(1, 11).to_string();
+		let lines: Vec<_> = torus.lines().collect();
+		assert_eq!(lines[0], "   0  1  2  3  4  5  6  7  8  9  10");
+		assert_eq!(lines[2], "0 ─┼──┼──┼──┼──┼──┼──┼──┼──┼──┼──┼─ 0");
+	}
+}
@@ -1,18 +1,68 @@
 use core::board::Board;
-use core::util::bool_mat::BoolMat;
 use core::util::bool_vec::BoolVec;
 use core::util::indexer::Indexer;
+use core::util::linear_map::LinearMap;
+use std::fmt;
+use std::mem;
+use std::rc::Rc;
 
 // TODO: Kick out the rules into their own module.
 // TODO: There is some renaming to be done.
 // TODO: Refactor the caching inftrastructure.
 //       * Caching should not be mandatory.
 
-#[derive(PartialEq, Eq)]
+/// What the rules need to know about a board over and over again,
+/// worked out once and shared by all positions of a game.
+struct Topology {
+	/// Whether every adjacency goes both ways.
+	symmetric: bool,
+	/// The points every point points to, besides itself,
+	/// by internal index.
+	out: Vec<Vec<usize>>,
+	/// The points pointing to every point, besides itself,
+	/// by internal index.
+	into: Vec<Vec<usize>>,
+}
+
+impl Topology {
+	fn new<T: Board>(board: &T) -> Self {
+		let indexer = board.adjacencies().rows().clone();
+		let mut out = vec![Vec::new(); indexer.range()];
+		let mut into = vec![Vec::new(); indexer.range()];
+		for (a, b) in board.adjacencies().entries() {
+			let (j, k) = (indexer.to_num(a), indexer.to_num(b));
+			if j != k {
+				out[j].push(k);
+				into[k].push(j);
+			}
+		}
+		Topology {
+			symmetric: board.is_symmetric(),
+			out,
+			into,
+		}
+	}
+}
+
+/// The stones of one player, sorted into groups.
+/// Rather than relating every pair of points,
+/// which takes memory quadratic in the size of the board,
+/// every stone carries the label of its group.
+/// The liberties of every group are kept up to date as well,
+/// which needs the stones of the opponent,
+/// so the game state tells us whenever points are taken or freed.
 struct PlayerState<'board, T: 'board + Board> {
 	board: &'board T,
+	topology: Rc<Topology>,
 	stones: BoolVec<T::I>,
-	connections: BoolMat<T::I, T::I>,
+	/// The label of the group of every stone, by internal index.
+	/// A label is the internal index of one of the stones of the group.
+	labels: Vec<Option<usize>>,
+	/// The internal indices of the stones of every group, by label.
+	members: Vec<Vec<usize>>,
+	/// The internal indices of the liberties of every group, by label,
+	/// in ascending order.
+	liberties: Vec<Vec<usize>>,
 }
 
 impl<'board, T> Clone for PlayerState<'board, T>
@@ -21,63 +71,221 @@ where
 {
 	fn clone(&self) -> Self {
 		let board = self.board;
+		let topology = self.topology.clone();
 		let stones = self.stones.clone();
-		let connections = self.connections.clone();
+		let labels = self.labels.clone();
+		let members = self.members.clone();
+		let liberties = self.liberties.clone();
 		PlayerState {
 			board,
+			topology,
 			stones,
-			connections,
+			labels,
+			members,
+			liberties,
 		}
 	}
 }
 
+// The labels depend on the order the stones were placed in,
+// the groups themselves only on the stones.
+impl<'board, T> PartialEq for PlayerState<'board, T>
+where
+	T: Board,
+{
+	fn eq(&self, other: &Self) -> bool {
+		self.stones == other.stones
+	}
+}
+
+impl<'board, T> Eq for PlayerState<'board, T> where T: Board {}
+
 impl<'board, T> PlayerState<'board, T>
 where
 	T: Board,
 {
-	fn place_stone(&mut self, i: <T::I as Indexer>::Index) {
-		self.stones[i] = true;
-		let diag = &BoolMat::from_diag(&self.stones);
-		let adj = self.board.adjacencies();
-		// We restrict ourselves to the adjacencies within our stones.
-		let adj = diag * adj * diag;
-		self.connections = {
-			let conn = &self.connections;
-			let new_connections = conn * adj * conn;
-			new_connections
+	fn new(board: &'board T, topology: Rc<Topology>) -> Self {
+		let indexer = board.adjacencies().rows().clone();
+		let range = indexer.range();
+		PlayerState {
+			board,
+			topology,
+			stones: BoolVec::falses(indexer),
+			labels: vec![None; range],
+			members: vec![Vec::new(); range],
+			liberties: vec![Vec::new(); range],
+		}
+	}
+
+	/// Put a stone on the point with the given internal index,
+	/// which every group has already been told is taken.
+	/// The opponent's stones tell which points are free.
+	fn place_stone(&mut self, n: usize, opponent: &BoolVec<T::I>) {
+		let indexer = self.stones.indexer().clone();
+		self.stones[indexer.to_index(n)] = true;
+		if !self.topology.symmetric {
+			// On directed boards, the new stone may join groups
+			// not adjacent to it,
+			// so we let the board connect everything anew.
+			self.regroup(opponent);
+			return;
+		}
+		// Otherwise, it merges exactly the groups next to it.
+		let topology = self.topology.clone();
+		let mut labels: Vec<_> = topology.out[n]
+			.iter()
+			.filter_map(|&k| self.labels[k])
+			.collect();
+		labels.sort_unstable();
+		labels.dedup();
+		// The largest group absorbs the others.
+		let label = labels
+			.iter()
+			.cloned()
+			.max_by_key(|&l| self.members[l].len())
+			.unwrap_or(n);
+		self.join(label, n);
+		let mut liberties = mem::take(&mut self.liberties[label]);
+		for l in labels.into_iter().filter(|&l| l != label) {
+			for k in mem::take(&mut self.members[l]) {
+				self.join(label, k);
+			}
+			liberties.append(&mut self.liberties[l]);
+		}
+		let free = |k: &&usize| {
+			!self.stones.as_slice()[**k]
+				&& !opponent.as_slice()[**k]
 		};
+		liberties.extend(topology.out[n].iter().filter(free));
+		liberties.sort_unstable();
+		liberties.dedup();
+		self.liberties[label] = liberties;
+	}
+
+	/// Add the stone with the given internal index to a group.
+	fn join(&mut self, label: usize, n: usize) {
+		self.labels[n] = Some(label);
+		self.members[label].push(n);
+	}
+
+	/// Sort all stones into groups
+	/// and find their liberties from scratch.
+	fn regroup(&mut self, opponent: &BoolVec<T::I>) {
+		let labels = self.board.components(&self.stones);
+		let range = labels.len();
+		self.labels = vec![None; range];
+		self.members = vec![Vec::new(); range];
+		self.liberties = vec![Vec::new(); range];
+		for (n, label) in labels.into_iter().enumerate() {
+			if let Some(label) = label {
+				self.join(label, n);
+			}
+		}
+		for label in 0..range {
+			let mut liberties: Vec<_> = self.members[label]
+				.iter()
+				.flat_map(|&k| {
+					self.topology.out[k].iter().cloned()
+				})
+				.filter(|&k| {
+					!self.stones.as_slice()[k]
+						&& !opponent.as_slice()[k]
+				})
+				.collect();
+			liberties.sort_unstable();
+			liberties.dedup();
+			self.liberties[label] = liberties;
+		}
+	}
+
+	/// Tell the groups that a point has been taken.
+	fn take(&mut self, n: usize) {
+		let topology = self.topology.clone();
+		for &k in &topology.into[n] {
+			if let Some(label) = self.labels[k] {
+				let liberties = &mut self.liberties[label];
+				if let Ok(pos) = liberties.binary_search(&n) {
+					liberties.remove(pos);
+				}
+			}
+		}
+	}
+
+	/// Tell the groups that a point has been freed.
+	fn release(&mut self, n: usize) {
+		let topology = self.topology.clone();
+		for &k in &topology.into[n] {
+			if let Some(label) = self.labels[k] {
+				let liberties = &mut self.liberties[label];
+				if let Err(pos) = liberties.binary_search(&n) {
+					liberties.insert(pos, n);
+				}
+			}
+		}
 	}
 
-	fn survivors(&self, free: &BoolVec<T::I>) -> BoolVec<T::I> {
-		let adj = self.board.adjacencies();
-		let conn = &self.connections;
-		(conn * adj).eval(free)
+	/// The stones of the group a stone belongs to.
+	fn group_of(&self, i: <T::I as Indexer>::Index) -> BoolVec<T::I> {
+		self.points_of(i, &self.members)
 	}
 
-	fn kill(&mut self, zombies: &BoolVec<T::I>) {
-		// The zombies infect everything in contact with them
-		let zombies = self.connections.eval(zombies);
-		// Retain all those that have not become zombies
-		self.stones = &self.stones & &!&zombies;
-		// Keep only the connections of the surviving groups
-		self.connections =
-			&self.connections * &BoolMat::from_diag(&self.stones);
+	/// The liberties of the group a stone belongs to.
+	fn liberties_of(&self, i: <T::I as Indexer>::Index) -> BoolVec<T::I> {
+		self.points_of(i, &self.liberties)
 	}
 
-	fn kill_dead(&mut self, liberties: &BoolVec<T::I>) {
-		let dead = self.survivors(liberties).complement();
-		self.kill(&dead);
+	/// The points listed for the group of a stone.
+	fn points_of(
+		&self,
+		i: <T::I as Indexer>::Index,
+		lists: &[Vec<usize>],
+	) -> BoolVec<T::I> {
+		let indexer = self.stones.indexer().clone();
+		let mut points = BoolVec::falses(indexer.clone());
+		if let Some(label) = self.labels[indexer.to_num(i)] {
+			for &k in &lists[label] {
+				points[indexer.to_index(k)] = true;
+			}
+		}
+		points
+	}
+
+	/// The labels of the groups without liberties.
+	fn dead(&self) -> Vec<usize> {
+		(0..self.members.len())
+			.filter(|&l| {
+				!self.members[l].is_empty()
+					&& self.liberties[l].is_empty()
+			})
+			.collect()
+	}
+
+	/// Take the groups without liberties off the board
+	/// and return the internal indices of their stones.
+	fn remove_dead(&mut self) -> Vec<usize> {
+		let indexer = self.stones.indexer().clone();
+		let mut removed = Vec::new();
+		for label in self.dead() {
+			for k in mem::take(&mut self.members[label]) {
+				self.labels[k] = None;
+				self.stones[indexer.to_index(k)] = false;
+				removed.push(k);
+			}
+		}
+		removed
 	}
 }
 
-#[derive(Clone, Copy, Eq, PartialEq)]
-enum Color {
+/// The colours of the stones, and thereby of the players.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Color {
 	Black,
 	White,
 }
 
 impl Color {
-	fn other(&self) -> Color {
+	/// The opponent's colour.
+	pub fn other(&self) -> Color {
 		match self {
 			Color::Black => Color::White,
 			Color::White => Color::Black,
@@ -85,8 +293,50 @@ impl Color {
 	}
 }
 
+/// A group of stones, also known as chain:
+/// stones of one colour connected to each other.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Group<I: Indexer> {
+	color: Color,
+	representative: I::Index,
+	stones: BoolVec<I>,
+	liberties: BoolVec<I>,
+}
+
+impl<I> Group<I>
+where
+	I: Indexer,
+{
+	/// The colour of the stones.
+	pub fn color(&self) -> Color {
+		self.color
+	}
+
+	/// The stone of the group which comes first in the indexer,
+	/// which identifies the group as long as it does not change.
+	pub fn representative(&self) -> I::Index {
+		self.representative
+	}
+
+	/// The stones belonging to the group.
+	pub fn stones(&self) -> &BoolVec<I> {
+		&self.stones
+	}
+
+	/// The free points the stones of the group point to.
+	pub fn liberties(&self) -> &BoolVec<I> {
+		&self.liberties
+	}
+
+	/// The number of liberties.
+	pub fn liberty_count(&self) -> usize {
+		self.liberties.count()
+	}
+}
+
+/// A position on a board, together with the player to move.
 #[derive(PartialEq, Eq)]
-struct GameState<'board, T: 'board + Board> {
+pub struct GameState<'board, T: 'board + Board> {
 	black: PlayerState<'board, T>,
 	white: PlayerState<'board, T>,
 	to_move: Color,
@@ -112,29 +362,274 @@ impl<'board, T> GameState<'board, T>
 where
 	T: Board,
 {
-	fn free(&self) -> BoolVec<T::I> {
-		let black = &self.black.stones;
-		let white = &self.white.stones;
-		BoolVec::union(black, white).complement()
+	/// The empty board, with Black to move.
+	pub fn new(board: &'board T) -> Self {
+		let topology = Rc::new(Topology::new(board));
+		let player = PlayerState::new(board, topology);
+		GameState {
+			black: player.clone(),
+			white: player,
+			to_move: Color::Black,
+		}
 	}
 
-	fn kill_dead(&mut self, color: Color) {
-		let liberties = self.free();
-		self.player_state(color).kill_dead(&liberties);
+	/// The player to move.
+	pub fn to_move(&self) -> Color {
+		self.to_move
+	}
+
+	/// The stones of one player.
+	pub fn stones(&self, color: Color) -> &BoolVec<T::I> {
+		&self.player(color).stones
+	}
+
+	/// The group a stone belongs to,
+	/// or `None` if the point is empty.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::Square;
+	/// # use gorrosion::core::game::{Color, GameState};
+	/// let square = Square::new(9);
+	/// let mut game = GameState::new(&square);
+	/// game.place_stone((0, 0), Color::Black);
+	/// game.place_stone((0, 1), Color::Black);
+	/// game.place_stone((1, 0), Color::White);
+	/// let group = game.group_at((0, 1)).unwrap();
+	/// assert_eq!(group.color(), Color::Black);
+	/// assert_eq!(group.representative(), (0, 0));
+	/// assert_eq!(group.stones().count(), 2);
+	/// assert_eq!(group.liberty_count(), 2);
+	/// assert!(group.liberties()[(1, 1)]);
+	/// assert!(game.group_at((5, 5)).is_none());
+	/// assert_eq!(game.groups().count(), 2);
+	/// ```
+	pub fn group_at(
+		&self,
+		i: <T::I as Indexer>::Index,
+	) -> Option<Group<T::I>> {
+		let color = if self.black.stones[i] {
+			Color::Black
+		} else if self.white.stones[i] {
+			Color::White
+		} else {
+			return None;
+		};
+		let player = self.player(color);
+		let indexer = player.stones.indexer();
+		let stones = player.group_of(i);
+		let liberties = player.liberties_of(i);
+		let first = stones.as_slice().iter().position(|&b| b);
+		let representative = indexer
+			.to_index(first.expect("a stone belongs to its group"));
+		Some(Group {
+			color,
+			representative,
+			stones,
+			liberties,
+		})
+	}
+
+	/// All groups on the board, ordered by their representatives.
+	pub fn groups<'a>(&'a self) -> impl Iterator<Item = Group<T::I>> + 'a {
+		let indexer = self.black.stones.indexer().clone();
+		let mut visited = vec![false; indexer.range()];
+		// The first stone of a group we come across is its representative.
+		(0..indexer.range()).filter_map(move |n| {
+			if visited[n] {
+				return None;
+			}
+			let group = self.group_at(indexer.to_index(n))?;
+			let stones = group.stones.as_slice();
+			for (k, _) in
+				stones.iter().enumerate().filter(|&(_, &b)| b)
+			{
+				visited[k] = true;
+			}
+			Some(group)
+		})
+	}
+
+	/// Make a move, which has to be legal,
+	/// and pass the turn to the opponent.
+	/// The stones of the opponent are captured first,
+	/// then, if suicide is allowed, one's own.
+	pub fn play(&self, m: Move<T>) -> Self {
+		let mut next = self.clone();
+		if let Action::Place(i) = m.action {
+			next.place_stone(i, m.player);
+			next.kill_dead(m.player.other());
+			next.kill_dead(m.player);
+		}
+		next.to_move = m.player.other();
+		next
+	}
+
+	/// Whether both positions have the same stones,
+	/// no matter who is to move.
+	pub fn same_stones(&self, other: &Self) -> bool {
+		self.black.stones == other.black.stones
+			&& self.white.stones == other.white.stones
+	}
+
+	/// A Zobrist hash of the stones on the board,
+	/// ignoring who is to move.
+	pub fn position_hash(&self) -> u64 {
+		let mut hash = 0;
+		for &color in &[Color::Black, Color::White] {
+			let stones = self.player(color).stones.as_slice();
+			for (n, _) in
+				stones.iter().enumerate().filter(|&(_, &b)| b)
+			{
+				hash ^= zobrist(n, color);
+			}
+		}
+		hash
+	}
+
+	/// The groups of one player with a single liberty left.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::Square;
+	/// # use gorrosion::core::game::{Color, GameState};
+	/// let square = Square::new(9);
+	/// let mut game = GameState::new(&square);
+	/// game.place_stone((0, 0), Color::White);
+	/// game.place_stone((0, 1), Color::Black);
+	/// game.place_stone((2, 0), Color::Black);
+	/// let atari: Vec<_> = game.groups_in_atari(Color::White).collect();
+	/// assert_eq!(atari.len(), 1);
+	/// assert!(atari[0].liberties()[(1, 0)]);
+	/// assert_eq!(game.liberties_at((0, 1)).unwrap().count(), 2);
+	/// // Black takes the last liberty.
+	/// assert!(game.captures((1, 0), Color::Black)[(0, 0)]);
+	/// // White escapes, if only into another atari.
+	/// assert!(game.self_atari((1, 0), Color::White));
+	/// ```
+	pub fn groups_in_atari<'a>(
+		&'a self,
+		color: Color,
+	) -> impl Iterator<Item = Group<T::I>> + 'a {
+		self.groups().filter(move |g| {
+			g.color == color && g.liberty_count() == 1
+		})
+	}
+
+	/// The liberties of the group a stone belongs to,
+	/// or `None` if the point is empty.
+	pub fn liberties_at(
+		&self,
+		i: <T::I as Indexer>::Index,
+	) -> Option<BoolVec<T::I>> {
+		self.group_at(i).map(|g| g.liberties)
+	}
+
+	/// The stones of the opponent a stone on an empty point would capture.
+	pub fn captures(
+		&self,
+		i: <T::I as Indexer>::Index,
+		color: Color,
+	) -> BoolVec<T::I> {
+		let mut next = self.clone();
+		next.place_stone(i, color);
+		let opponent = next.player(color.other());
+		let indexer = opponent.stones.indexer();
+		let mut captured = BoolVec::falses(indexer.clone());
+		for label in opponent.dead() {
+			for &k in &opponent.members[label] {
+				captured[indexer.to_index(k)] = true;
+			}
+		}
+		captured
+	}
+
+	/// Whether a stone on an empty point would end up
+	/// in a group with a single liberty,
+	/// after capturing whatever it captures.
+	/// Suicide does not count as self-atari.
+	pub fn self_atari(
+		&self,
+		i: <T::I as Indexer>::Index,
+		color: Color,
+	) -> bool {
+		let action = Action::Place(i);
+		let next = self.play(Move {
+			player: color,
+			action,
+		});
+		next.group_at(i).is_some_and(|g| g.liberty_count() == 1)
 	}
 
-	fn player_state(
+	/// The groups of the opponent which a stone on an empty point
+	/// would take a liberty from, leaving them with `liberties` ones.
+	/// The groups are reported as they are right after the stone is placed,
+	/// so for no liberties at all they are the ones about to be captured.
+	pub fn reduces_to(
+		&self,
+		i: <T::I as Indexer>::Index,
+		color: Color,
+		liberties: usize,
+	) -> Vec<Group<T::I>> {
+		let mut next = self.clone();
+		next.place_stone(i, color);
+		let touched = |g: &Group<T::I>| {
+			self.liberties_at(g.representative)
+				.is_some_and(|before| before[i])
+		};
+		next.groups()
+			.filter(|g| g.color == color.other())
+			.filter(|g| {
+				g.liberty_count() == liberties && touched(g)
+			})
+			.collect()
+	}
+
+	/// Put a stone on an empty point,
+	/// without capturing anything,
+	/// e.g. to set up a problem.
+	/// Panics if the point is occupied already.
+	pub fn place_stone(
 		&mut self,
+		i: <T::I as Indexer>::Index,
 		color: Color,
-	) -> &mut PlayerState<'board, T> {
+	) {
+		let occupied = self.black.stones[i] || self.white.stones[i];
+		assert!(!occupied, "The point is occupied already");
+		let n = self.black.stones.indexer().to_num(i);
+		self.black.take(n);
+		self.white.take(n);
+		let (player, opponent) = match color {
+			Color::Black => (&mut self.black, &self.white),
+			Color::White => (&mut self.white, &self.black),
+		};
+		player.place_stone(n, &opponent.stones);
+	}
+
+	fn player(&self, color: Color) -> &PlayerState<'board, T> {
 		match color {
-			Color::Black => &mut self.black,
-			Color::White => &mut self.white,
+			Color::Black => &self.black,
+			Color::White => &self.white,
 		}
 	}
 
-	fn place_stone(&mut self, i: <T::I as Indexer>::Index, color: Color) {
-		self.player_state(color).place_stone(i);
+	fn free(&self) -> BoolVec<T::I> {
+		let black = &self.black.stones;
+		let white = &self.white.stones;
+		BoolVec::union(black, white).complement()
+	}
+
+	fn kill_dead(&mut self, color: Color) {
+		let removed = match color {
+			Color::Black => self.black.remove_dead(),
+			Color::White => self.white.remove_dead(),
+		};
+		for n in removed {
+			self.black.release(n);
+			self.white.release(n);
+		}
 	}
 }
 
@@ -161,36 +656,1015 @@ where Action<T>: Copy {
 
 */
 
-#[derive(Copy, Clone)]
-enum Action<Index: Copy> {
+/// The key of a stone of some colour on the point
+/// with the given internal index for Zobrist hashing.
+/// Rather than storing a table of random numbers,
+/// we scramble the index with SplitMix64.
+fn zobrist(n: usize, color: Color) -> u64 {
+	let colour = match color {
+		Color::Black => 0,
+		Color::White => 1,
+	};
+	let mut z =
+		(2 * n as u64 + colour + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+	z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+	z ^ (z >> 31)
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Action<Index: Copy> {
 	Pass,
 	Resign,
 	Place(Index),
 }
 
-#[derive(Copy, Clone)]
-struct Move<T: Board> {
-	player: Color,
-	action: Action<<T::I as Indexer>::Index>,
+pub struct Move<T: Board> {
+	pub player: Color,
+	pub action: Action<<T::I as Indexer>::Index>,
 }
 
-struct LocalRules {
-	suicide_allowed: bool,
-	alternate_play: bool,
+// Deriving these would require the board to implement them as well.
+impl<T: Board> Clone for Move<T> {
+	fn clone(&self) -> Self {
+		*self
+	}
 }
 
-struct Rules {
-	local_rules: LocalRules,
-	superko: bool,
+impl<T: Board> Copy for Move<T> {}
+
+impl<T: Board> PartialEq for Move<T>
+where
+	<T::I as Indexer>::Index: PartialEq,
+{
+	fn eq(&self, other: &Self) -> bool {
+		self.player == other.player && self.action == other.action
+	}
+}
+
+impl<T: Board> Eq for Move<T> where <T::I as Indexer>::Index: Eq {}
+
+impl<T: Board> fmt::Debug for Move<T>
+where
+	<T::I as Indexer>::Index: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:?} {:?}", self.player, self.action)
+	}
+}
+
+pub struct LocalRules {
+	pub suicide_allowed: bool,
+	pub alternate_play: bool,
+}
+
+pub struct Rules {
+	pub local_rules: LocalRules,
+	/// Forbid moves repeating any earlier position,
+	/// rather than just the one before the opponent's last move.
+	pub superko: bool,
 	// This is twice the value of komi to allow for half points
-	bikomi: usize,
-	fixed_handicap: bool,
+	pub bikomi: usize,
+	pub fixed_handicap: bool,
 }
 
-struct GameNode<'a, 'board: 'a, T: 'board + Board> {
+/// Positional superko without suicide and a komi of 6.5.
+impl Default for Rules {
+	fn default() -> Self {
+		let local_rules = LocalRules {
+			suicide_allowed: false,
+			alternate_play: true,
+		};
+		Rules {
+			local_rules,
+			superko: true,
+			bikomi: 13,
+			fixed_handicap: false,
+		}
+	}
+}
+
+/// A position together with the way it was reached,
+/// as far as the rules need to know about it.
+pub struct GameNode<'a, 'board: 'a, T: 'board + Board> {
 	state: GameState<'board, T>,
-	// TODO: Think about this triple lifetime ('b, 'b, 'b).
-	//       Is this actually the intended behaviour?
-	prev_node: Option<&'a GameNode<'a, 'a, T>>,
+	prev_node: Option<&'a GameNode<'a, 'board, T>>,
 	last_move: Option<Move<T>>,
+	hash: u64,
+}
+
+impl<'a, 'board, T> GameNode<'a, 'board, T>
+where
+	T: Board,
+{
+	/// Start a game from a position.
+	pub fn new(state: GameState<'board, T>) -> Self {
+		let hash = state.position_hash();
+		GameNode {
+			state,
+			prev_node: None,
+			last_move: None,
+			hash,
+		}
+	}
+
+	/// The current position.
+	pub fn state(&self) -> &GameState<'board, T> {
+		&self.state
+	}
+
+	/// The move leading here, if any.
+	pub fn last_move(&self) -> Option<Move<T>> {
+		self.last_move
+	}
+
+	/// Continue the game with a move, which has to be legal.
+	pub fn play<'b>(&'b self, m: Move<T>) -> GameNode<'b, 'board, T> {
+		let state = self.state.play(m);
+		let hash = state.position_hash();
+		GameNode {
+			state,
+			prev_node: Some(self),
+			last_move: Some(m),
+			hash,
+		}
+	}
+
+	/// Every legal move of the player to move,
+	/// placements in the order of the indexer,
+	/// then passing and resigning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::Square;
+	/// # use gorrosion::core::game::{Action, Color, GameNode, GameState, Move, Rules};
+	/// let square = Square::new(3);
+	/// let mut state = GameState::new(&square);
+	/// state.place_stone((0, 1), Color::White);
+	/// state.place_stone((1, 0), Color::White);
+	/// let game = GameNode::new(state);
+	/// let rules = Rules::default();
+	/// // Playing into the corner would be suicide.
+	/// assert!(!game.legal_points(&rules)[(0, 0)]);
+	/// let moves = game.legal_moves(&rules);
+	/// assert_eq!(moves.len(), 6 + 2);
+	/// assert_eq!(
+	///	moves[0],
+	///	Move { player: Color::Black, action: Action::Place((0, 2)) },
+	/// );
+	/// ```
+	pub fn legal_moves(&self, rules: &Rules) -> Vec<Move<T>> {
+		let player = self.state.to_move;
+		let legal = self.legal_points(rules);
+		let indexer = legal.indexer();
+		let places = (0..indexer.range())
+			.filter(|&n| legal.as_slice()[n])
+			.map(|n| Action::Place(indexer.to_index(n)));
+		places.chain(vec![Action::Pass, Action::Resign])
+			.map(|action| Move { player, action })
+			.collect()
+	}
+
+	/// The points the player to move may place a stone on.
+	/// On symmetric boards, this only needs the liberties of the groups
+	/// and never actually makes a move
+	/// unless the Zobrist hash hints at a repetition.
+	pub fn legal_points(&self, rules: &Rules) -> BoolVec<T::I> {
+		let state = &self.state;
+		let topology = &state.black.topology;
+		let free = state.free();
+		let indexer = free.indexer().clone();
+		let mut legal = BoolVec::falses(indexer.clone());
+		let color = state.to_move;
+		let place = |i| Move {
+			player: color,
+			action: Action::Place(i),
+		};
+		if !topology.symmetric {
+			// Liberties need not lie next to the stone placed,
+			// so we simply try every move.
+			for n in (0..indexer.range())
+				.filter(|&n| free.as_slice()[n])
+			{
+				let i = indexer.to_index(n);
+				let next = state.play(place(i));
+				let suicide = !next.player(color).stones[i];
+				if suicide && !rules.local_rules.suicide_allowed
+				{
+					continue;
+				}
+				legal[i] = !self.repeats(
+					next.position_hash(),
+					|| next.clone(),
+					rules,
+				);
+			}
+			return legal;
+		}
+		let own = state.player(color);
+		let theirs = state.player(color.other());
+		// The Zobrist hash of the stones of a group.
+		let hash = |color: Color, label: usize| {
+			state.player(color).members[label]
+				.iter()
+				.fold(0, |hash, &k| hash ^ zobrist(k, color))
+		};
+		for n in (0..indexer.range()).filter(|&n| free.as_slice()[n]) {
+			let mut breathes = false;
+			let mut captured = Vec::new();
+			let mut joined = Vec::new();
+			for &k in &topology.out[n] {
+				if let Some(l) = own.labels[k] {
+					// The point itself is one of the liberties.
+					breathes |= own.liberties[l].len() > 1;
+					joined.push(l);
+				} else if let Some(l) = theirs.labels[k] {
+					if theirs.liberties[l].len() == 1 {
+						captured.push(l);
+					}
+				} else {
+					breathes = true;
+				}
+			}
+			captured.sort_unstable();
+			captured.dedup();
+			joined.sort_unstable();
+			joined.dedup();
+			let suicide = !breathes && captured.is_empty();
+			if suicide && !rules.local_rules.suicide_allowed {
+				continue;
+			}
+			let mut next_hash = self.hash ^ zobrist(n, color);
+			for &l in &captured {
+				next_hash ^= hash(color.other(), l);
+			}
+			if suicide {
+				next_hash ^= zobrist(n, color);
+				for &l in &joined {
+					next_hash ^= hash(color, l);
+				}
+			}
+			let i = indexer.to_index(n);
+			legal[i] = !self.repeats(
+				next_hash,
+				|| state.play(place(i)),
+				rules,
+			);
+		}
+		legal
+	}
+
+	/// Whether both players passed in a row, ending the game.
+	pub fn is_over(&self) -> bool {
+		let passed = |node: &Self| {
+			matches!(
+				node.last_move,
+				Some(Move {
+					action: Action::Pass,
+					..
+				})
+			)
+		};
+		passed(self) && self.prev_node.is_some_and(passed)
+	}
+
+	/// Count the sequences of `depth` legal moves from here,
+	/// the way chess programmers verify their move generators.
+	/// Moves are placements and passes but not resignations,
+	/// and no move follows two passes in a row.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::Square;
+	/// # use gorrosion::core::game::{GameNode, GameState, Rules};
+	/// let square = Square::new(2);
+	/// let game = GameNode::new(GameState::new(&square));
+	/// // Four placements and a pass.
+	/// assert_eq!(game.perft(&Rules::default(), 1), 5);
+	/// ```
+	pub fn perft(&self, rules: &Rules, depth: usize) -> usize {
+		self.perft_with(
+			rules,
+			depth,
+			&|node: &GameNode<'_, 'board, T>, rules: &Rules| {
+				node.legal_points(rules)
+			},
+		)
+	}
+
+	/// Split the count of `perft` by the first move.
+	pub fn perft_divide(
+		&self,
+		rules: &Rules,
+		depth: usize,
+	) -> Vec<(Move<T>, usize)> {
+		if depth == 0 {
+			return Vec::new();
+		}
+		self.perft_moves(
+			rules,
+			&|node: &GameNode<'_, 'board, T>, rules: &Rules| {
+				node.legal_points(rules)
+			},
+		)
+		.into_iter()
+		.map(|m| (m, self.play(m).perft(rules, depth - 1)))
+		.collect()
+	}
+
+	/// The moves `perft` follows,
+	/// with the legal points found by `generate`.
+	fn perft_moves<G>(&self, rules: &Rules, generate: &G) -> Vec<Move<T>>
+	where
+		G: Fn(&GameNode<'_, 'board, T>, &Rules) -> BoolVec<T::I>,
+	{
+		if self.is_over() {
+			return Vec::new();
+		}
+		let player = self.state.to_move;
+		let legal = generate(self, rules);
+		let indexer = legal.indexer();
+		(0..indexer.range())
+			.filter(|&n| legal.as_slice()[n])
+			.map(|n| Action::Place(indexer.to_index(n)))
+			.chain(Some(Action::Pass))
+			.map(|action| Move { player, action })
+			.collect()
+	}
+
+	/// `perft` with the legal points found by `generate`,
+	/// to compare different move generators.
+	fn perft_with<G>(
+		&self,
+		rules: &Rules,
+		depth: usize,
+		generate: &G,
+	) -> usize
+	where
+		G: Fn(&GameNode<'_, 'board, T>, &Rules) -> BoolVec<T::I>,
+	{
+		if depth == 0 {
+			return 1;
+		}
+		let moves = self.perft_moves(rules, generate);
+		if depth == 1 {
+			return moves.len();
+		}
+		moves.into_iter()
+			.map(|m| {
+				self.play(m).perft_with(
+					rules,
+					depth - 1,
+					generate,
+				)
+			})
+			.sum()
+	}
+
+	/// Whether the position with the given hash,
+	/// reached by the move still to be made,
+	/// repeats an earlier one the rules forbid to repeat.
+	/// Positions are only compared in full if the hashes agree.
+	fn repeats<F>(&self, hash: u64, next: F, rules: &Rules) -> bool
+	where
+		F: FnOnce() -> GameState<'board, T>,
+	{
+		let earlier: Vec<_> = if rules.superko {
+			let mut earlier = Vec::new();
+			let mut node = Some(self);
+			while let Some(n) = node {
+				earlier.push(n);
+				node = n.prev_node;
+			}
+			earlier
+		} else {
+			// Simple ko only forbids undoing the opponent's last move.
+			self.prev_node.into_iter().collect()
+		};
+		let candidates: Vec<_> = earlier
+			.into_iter()
+			.filter(|n| n.hash == hash)
+			.collect();
+		if candidates.is_empty() {
+			return false;
+		}
+		let next = next();
+		candidates.iter().any(|n| n.state.same_stones(&next))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::board::{
+		Connectivity, Digraph, Graph, Hexagon, Square, Torus, Triangle,
+	};
+
+	#[test]
+	fn capture_across_glued_edges() {
+		let torus = Torus::new(5, 5);
+		let mut game = GameState::new(&torus);
+		game.place_stone((0, 0), Color::Black);
+		// On a torus, the corner has four neighbours,
+		// all but one of them across an edge.
+		for &i in &[(1, 0), (0, 1), (4, 0)] {
+			game.place_stone(i, Color::White);
+			game.kill_dead(Color::Black);
+			assert!(game.black.stones[(0, 0)]);
+		}
+		game.place_stone((0, 4), Color::White);
+		game.kill_dead(Color::Black);
+		assert!(!game.black.stones[(0, 0)]);
+		game.kill_dead(Color::White);
+		assert_eq!(game.white.stones.count(), 4);
+	}
+
+	#[test]
+	fn capture_on_other_tilings() {
+		let triangle = Triangle::new(3);
+		let mut game = GameState::new(&triangle);
+		// The apex of a triangle has a single neighbour.
+		game.place_stone((0, 0), Color::Black);
+		game.place_stone((1, 1), Color::White);
+		game.kill_dead(Color::Black);
+		assert_eq!(game.black.stones.count(), 0);
+
+		let hexagon = Hexagon::new(3);
+		let mut game = GameState::new(&hexagon);
+		game.place_stone((0, 0), Color::Black);
+		let around = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, -1)];
+		for &i in &around {
+			game.place_stone(i, Color::White);
+		}
+		game.kill_dead(Color::Black);
+		assert!(game.black.stones[(0, 0)]);
+		game.place_stone((-1, 1), Color::White);
+		game.kill_dead(Color::Black);
+		assert!(!game.black.stones[(0, 0)]);
+	}
+
+	#[test]
+	fn groups_merge() {
+		let torus = Torus::new(5, 5);
+		let mut game = GameState::new(&torus);
+		// Two separate stones, joined by a third one in between.
+		game.place_stone((2, 1), Color::Black);
+		game.place_stone((2, 3), Color::Black);
+		assert!(!game.black.group_of((2, 1))[(2, 3)]);
+		game.place_stone((2, 2), Color::Black);
+		assert!(game.black.group_of((2, 1))[(2, 3)]);
+		assert!(game.black.group_of((2, 3))[(2, 1)]);
+		assert_eq!(game.black.group_of((2, 2)).count(), 3);
+	}
+
+	#[test]
+	fn capture_on_directed_boards() {
+		// A directed cycle 0 → 1 → 2 → 3 → 4 → 0
+		// with a shortcut 0 → 4.
+		let arcs = [(0, 1), (1, 2), (2, 3), (3, 4), (4, 0), (0, 4)];
+		for &connectivity in &[Connectivity::Strong, Connectivity::Weak]
+		{
+			let board: Digraph<_> = arcs
+				.iter()
+				.fold(Graph::builder(5), |b, &(j, k)| {
+					b.arc(j, k)
+				})
+				.build_directed(connectivity)
+				.unwrap();
+			let mut game = GameState::new(&board);
+			game.place_stone(0, Color::Black);
+			game.place_stone(1, Color::Black);
+			// The stone on 1 only points to 2,
+			// while the stone on 0 also points to 4.
+			game.place_stone(2, Color::White);
+			game.kill_dead(Color::Black);
+			assert!(game.black.stones[0]);
+			assert_eq!(
+				game.black.stones[1],
+				connectivity == Connectivity::Weak
+			);
+		}
+	}
+
+	#[test]
+	fn doctests() {
+		let square = Square::new(9);
+		let mut game = GameState::new(&square);
+		game.place_stone((0, 0), Color::Black);
+		game.place_stone((0, 1), Color::Black);
+		game.place_stone((1, 0), Color::White);
+		let group = game.group_at((0, 1)).unwrap();
+		assert_eq!(group.color(), Color::Black);
+		assert_eq!(group.representative(), (0, 0));
+		assert_eq!(group.stones().count(), 2);
+		assert_eq!(group.liberty_count(), 2);
+		assert!(group.liberties()[(1, 1)]);
+		assert!(game.group_at((5, 5)).is_none());
+		assert_eq!(game.groups().count(), 2);
+
+		let mut game = GameState::new(&square);
+		game.place_stone((0, 0), Color::White);
+		game.place_stone((0, 1), Color::Black);
+		game.place_stone((2, 0), Color::Black);
+		let atari: Vec<_> =
+			game.groups_in_atari(Color::White).collect();
+		assert_eq!(atari.len(), 1);
+		assert!(atari[0].liberties()[(1, 0)]);
+		assert_eq!(game.liberties_at((0, 1)).unwrap().count(), 2);
+		assert!(game.captures((1, 0), Color::Black)[(0, 0)]);
+		assert!(game.self_atari((1, 0), Color::White));
+
+		let square = Square::new(3);
+		let mut state = GameState::new(&square);
+		state.place_stone((0, 1), Color::White);
+		state.place_stone((1, 0), Color::White);
+		let game = GameNode::new(state);
+		let rules = Rules::default();
+		assert!(!game.legal_points(&rules)[(0, 0)]);
+		let moves = game.legal_moves(&rules);
+		assert_eq!(moves.len(), 6 + 2);
+		assert_eq!(
+			moves[0],
+			Move {
+				player: Color::Black,
+				action: Action::Place((0, 2))
+			},
+		);
+	}
+
+	#[test]
+	fn groups() {
+		let square = Square::new(5);
+		let mut game = GameState::new(&square);
+		for &i in &[(1, 1), (1, 2), (2, 2), (4, 4)] {
+			game.place_stone(i, Color::White);
+		}
+		for &i in &[(0, 1), (3, 4)] {
+			game.place_stone(i, Color::Black);
+		}
+		let groups: Vec<_> = game.groups().collect();
+		let summary: Vec<_> = groups
+			.iter()
+			.map(|g| {
+				let size = g.stones().count();
+				(
+					g.representative(),
+					g.color(),
+					size,
+					g.liberty_count(),
+				)
+			})
+			.collect();
+		assert_eq!(
+			summary,
+			vec![
+				((0, 1), Color::Black, 1, 2),
+				((1, 1), Color::White, 3, 6),
+				((3, 4), Color::Black, 1, 2),
+				((4, 4), Color::White, 1, 1),
+			]
+		);
+		// Every stone finds its own group.
+		for group in &groups {
+			let indexer = group.stones().indexer();
+			for n in 0..indexer.range() {
+				let i = indexer.to_index(n);
+				if group.stones()[i] {
+					assert_eq!(
+						game.group_at(i).as_ref(),
+						Some(group)
+					);
+				}
+			}
+		}
+		// Captures dissolve groups.
+		game.place_stone((4, 3), Color::Black);
+		game.kill_dead(Color::White);
+		assert!(game.group_at((4, 4)).is_none());
+		assert_eq!(game.group_at((3, 4)).unwrap().liberty_count(), 3);
+	}
+
+	#[test]
+	#[should_panic]
+	fn place_on_occupied_point() {
+		let square = Square::new(5);
+		let mut game = GameState::new(&square);
+		game.place_stone((2, 2), Color::Black);
+		game.place_stone((2, 2), Color::White);
+	}
+
+	#[test]
+	fn liberties_on_directed_boards() {
+		// A one-way street 0 → 1 → 2.
+		let board: Digraph<_> = Graph::builder(3)
+			.arc(0, 1)
+			.arc(1, 2)
+			.build_directed(Connectivity::Weak)
+			.unwrap();
+		let mut game = GameState::new(&board);
+		game.place_stone(1, Color::Black);
+		let group = game.group_at(1).unwrap();
+		assert_eq!(group.liberty_count(), 1);
+		assert!(group.liberties()[2]);
+		// The end of the street has no liberties at all.
+		let root = GameNode::new(GameState::new(&board));
+		let legal = root.legal_points(&Rules::default());
+		assert_eq!(legal.as_slice(), &[true, true, false]);
+	}
+
+	#[test]
+	fn tactics() {
+		let square = Square::new(5);
+		let mut game = GameState::new(&square);
+		// A white pair with three liberties and a lone stone with two.
+		for &i in &[(1, 1), (1, 2), (0, 4)] {
+			game.place_stone(i, Color::White);
+		}
+		for &i in &[(0, 1), (0, 2), (1, 0), (1, 4)] {
+			game.place_stone(i, Color::Black);
+		}
+		assert_eq!(game.groups_in_atari(Color::White).count(), 1);
+		assert_eq!(game.groups_in_atari(Color::Black).count(), 0);
+		assert!(game.liberties_at((2, 2)).is_none());
+		assert_eq!(game.liberties_at((1, 2)).unwrap().count(), 3);
+		// Black at (0, 3) captures the lone stone,
+		// Black at (1, 3) takes a liberty from the pair.
+		let captured = game.captures((0, 3), Color::Black);
+		assert_eq!(captured.count(), 1);
+		assert!(captured[(0, 4)]);
+		let reduced = game.reduces_to((1, 3), Color::Black, 2);
+		assert_eq!(reduced.len(), 1);
+		assert_eq!(reduced[0].representative(), (1, 1));
+		let reduced = game.reduces_to((0, 3), Color::Black, 0);
+		assert_eq!(reduced.len(), 1);
+		assert_eq!(reduced[0].representative(), (0, 4));
+		// Groups not touched by the move are never reported.
+		assert!(game.reduces_to((4, 4), Color::Black, 3).is_empty());
+		assert!(game.captures((4, 4), Color::Black).count() == 0);
+		// Black at (2, 1) ataris nothing, White at (0, 3) is in atari.
+		assert!(game.reduces_to((2, 1), Color::Black, 1).is_empty());
+		assert!(game.self_atari((0, 3), Color::White));
+		assert!(!game.self_atari((2, 2), Color::White));
+		// Capturing out of an atari is no self-atari.
+		let mut game = GameState::new(&square);
+		for &i in &[(0, 1), (1, 0)] {
+			game.place_stone(i, Color::Black);
+		}
+		for &i in &[(0, 2), (1, 1), (2, 0)] {
+			game.place_stone(i, Color::White);
+		}
+		assert!(game.captures((0, 0), Color::White).count() == 2);
+		assert!(!game.self_atari((0, 0), Color::White));
+		// Suicide is not self-atari either.
+		let mut game = GameState::new(&square);
+		for &i in &[(0, 1), (1, 0)] {
+			game.place_stone(i, Color::White);
+		}
+		assert!(!game.self_atari((0, 0), Color::Black));
+	}
+
+	/// The legal points found by making every move
+	/// and comparing the result to the whole history.
+	fn legal_by_trial<T: Board>(
+		node: &GameNode<'_, '_, T>,
+		rules: &Rules,
+	) -> BoolVec<T::I> {
+		let state = node.state();
+		let color = state.to_move();
+		let free = state.free();
+		let indexer = free.indexer().clone();
+		let mut legal = BoolVec::falses(indexer.clone());
+		for n in 0..indexer.range() {
+			let i = indexer.to_index(n);
+			if !free[i] {
+				continue;
+			}
+			let action = Action::Place(i);
+			let next = state.play(Move {
+				player: color,
+				action,
+			});
+			if !next.stones(color)[i]
+				&& !rules.local_rules.suicide_allowed
+			{
+				continue;
+			}
+			let mut earlier = Some(node);
+			let mut depth = 0;
+			legal[i] = true;
+			while let Some(e) = earlier {
+				if (rules.superko || depth == 1)
+					&& e.state().same_stones(&next)
+				{
+					legal[i] = false;
+				}
+				earlier = e.prev_node;
+				depth += 1;
+			}
+		}
+		legal
+	}
+
+	/// The stones of a set whose groups keep a liberty
+	/// next to the stones of the opponent,
+	/// with the groups taken from the matrix of connections.
+	fn survivors<T: Board>(
+		board: &T,
+		stones: &BoolVec<T::I>,
+		opponent: &BoolVec<T::I>,
+	) -> BoolVec<T::I> {
+		let free = !&BoolVec::union(stones, opponent);
+		let breathing = &board.adjacencies().eval(&free) & stones;
+		&board.connections(stones).eval(&breathing) & stones
+	}
+
+	/// The legal points found by working out every move with matrices,
+	/// sharing nothing with the groups the game keeps track of.
+	fn legal_by_matrix<T: Board>(
+		node: &GameNode<'_, '_, T>,
+		rules: &Rules,
+	) -> BoolVec<T::I> {
+		let state = node.state();
+		let board = state.black.board;
+		let color = state.to_move();
+		let free = state.free();
+		let indexer = free.indexer().clone();
+		let mut legal = BoolVec::falses(indexer.clone());
+		for n in 0..indexer.range() {
+			let i = indexer.to_index(n);
+			if !free[i] {
+				continue;
+			}
+			let mut own = state.stones(color).clone();
+			own[i] = true;
+			let theirs = survivors(
+				board,
+				state.stones(color.other()),
+				&own,
+			);
+			let own = survivors(board, &own, &theirs);
+			if !own[i] && !rules.local_rules.suicide_allowed {
+				continue;
+			}
+			let (black, white) = match color {
+				Color::Black => (own, theirs),
+				Color::White => (theirs, own),
+			};
+			let mut earlier = Some(node);
+			let mut depth = 0;
+			legal[i] = true;
+			while let Some(e) = earlier {
+				if (rules.superko || depth == 1)
+					&& e.state().stones(Color::Black)
+						== &black && e.state().stones(Color::White)
+					== &white
+				{
+					legal[i] = false;
+				}
+				earlier = e.prev_node;
+				depth += 1;
+			}
+		}
+		legal
+	}
+
+	#[test]
+	fn ko() {
+		// . B W .
+		// B W . W
+		// . B W .
+		// . . . .
+		let square = Square::new(4);
+		let mut state = GameState::new(&square);
+		for &i in &[(0, 1), (1, 0), (2, 1)] {
+			state.place_stone(i, Color::Black);
+		}
+		for &i in &[(0, 2), (1, 1), (1, 3), (2, 2)] {
+			state.place_stone(i, Color::White);
+		}
+		for &superko in &[false, true] {
+			let rules = Rules {
+				superko,
+				..Rules::default()
+			};
+			let root = GameNode::new(state.clone());
+			let take = Move {
+				player: Color::Black,
+				action: Action::Place((1, 2)),
+			};
+			assert!(root.legal_moves(&rules).contains(&take));
+			let taken = root.play(take);
+			assert_eq!(
+				taken.state().stones(Color::White).count(),
+				3
+			);
+			// White may not take back immediately.
+			let legal = taken.legal_points(&rules);
+			assert!(!legal[(1, 1)]);
+			assert_eq!(legal, legal_by_trial(&taken, &rules));
+			// After an exchange elsewhere, the ko may be retaken.
+			let threat = taken.play(Move {
+				player: Color::White,
+				action: Action::Place((3, 3)),
+			});
+			let answer = threat.play(Move {
+				player: Color::Black,
+				action: Action::Place((3, 2)),
+			});
+			assert!(answer.legal_points(&rules)[(1, 1)]);
+		}
+	}
+
+	#[test]
+	fn suicide() {
+		// B . W
+		// W W W
+		// . . .
+		let square = Square::new(3);
+		let mut state = GameState::new(&square);
+		state.place_stone((0, 0), Color::Black);
+		for &i in &[(0, 2), (1, 0), (1, 1), (1, 2)] {
+			state.place_stone(i, Color::White);
+		}
+		let root = GameNode::new(state);
+		let mut rules = Rules::default();
+		assert!(!root.legal_points(&rules)[(0, 1)]);
+		rules.local_rules.suicide_allowed = true;
+		let legal = root.legal_points(&rules);
+		assert!(legal[(0, 1)]);
+		assert_eq!(legal, legal_by_trial(&root, &rules));
+		let suicide = root.play(Move {
+			player: Color::Black,
+			action: Action::Place((0, 1)),
+		});
+		assert_eq!(suicide.state().stones(Color::Black).count(), 0);
+
+		// . W .
+		// W . .
+		// . . .
+		let mut state = GameState::new(&square);
+		state.place_stone((0, 1), Color::White);
+		state.place_stone((1, 0), Color::White);
+		let root = GameNode::new(state);
+		// A single stone killing itself changes nothing,
+		// which superko forbids.
+		assert!(!root.legal_points(&rules)[(0, 0)]);
+		rules.superko = false;
+		let legal = root.legal_points(&rules);
+		assert!(legal[(0, 0)]);
+		assert_eq!(legal, legal_by_trial(&root, &rules));
+	}
+
+	#[test]
+	fn incremental_agrees_with_trial() {
+		// Play a fixed pseudo-random game on a small board
+		// and compare both ways of finding the legal moves.
+		let square = Square::new(4);
+		let torus = Torus::new(3, 4);
+		// A ring of eight points with one-way chords.
+		let ring: Digraph<_> = (0..8)
+			.fold(Graph::builder(8), |b, k| {
+				b.edge(k, (k + 1) % 8).arc(k, (k + 3) % 8)
+			})
+			.build_directed(Connectivity::Strong)
+			.unwrap();
+		for &superko in &[false, true] {
+			let rules = Rules {
+				superko,
+				..Rules::default()
+			};
+			check_playout(
+				GameNode::new(GameState::new(&square)),
+				&rules,
+				1,
+				60,
+			);
+			check_playout(
+				GameNode::new(GameState::new(&torus)),
+				&rules,
+				7,
+				60,
+			);
+			check_playout(
+				GameNode::new(GameState::new(&ring)),
+				&rules,
+				3,
+				30,
+			);
+		}
+	}
+
+	fn check_playout<T: Board>(
+		node: GameNode<'_, '_, T>,
+		rules: &Rules,
+		seed: usize,
+		depth: usize,
+	) {
+		let legal = node.legal_points(rules);
+		assert_eq!(legal, legal_by_trial(&node, rules));
+		// The liberties kept up to date agree with a fresh count.
+		let state = node.state();
+		let free = state.free();
+		let adj = state.black.board.adjacencies();
+		for group in state.groups() {
+			let mut liberties =
+				BoolVec::falses(free.indexer().clone());
+			for (a, b) in adj.entries() {
+				if group.stones()[a] && free[b] {
+					liberties[b] = true;
+				}
+			}
+			assert_eq!(group.liberties(), &liberties);
+		}
+		if depth == 0 {
+			return;
+		}
+		let moves = node.legal_moves(rules);
+		// Never resign, and pass only when nothing else is left.
+		let choices = moves.len() - 2;
+		let m = if choices == 0 {
+			moves[0]
+		} else {
+			moves[(seed * 7919 + depth * 104_729) % choices]
+		};
+		check_playout(node.play(m), rules, seed, depth - 1);
+	}
+
+	#[test]
+	fn perft() {
+		let rules = Rules::default();
+		let square = Square::new(2);
+		let root = GameNode::new(GameState::new(&square));
+		assert_eq!(root.perft(&rules, 0), 1);
+		assert_eq!(root.perft(&rules, 1), 5);
+		// Passing twice ends the game.
+		let pass = Move {
+			player: Color::Black,
+			action: Action::Pass,
+		};
+		let passed = root.play(pass);
+		let pass = Move {
+			player: Color::White,
+			action: Action::Pass,
+		};
+		let over = passed.play(pass);
+		assert!(over.is_over());
+		assert_eq!(over.perft(&rules, 1), 0);
+		let divided = root.perft_divide(&rules, 3);
+		assert_eq!(divided.len(), 5);
+		let total: usize = divided.iter().map(|&(_, n)| n).sum();
+		assert_eq!(total, root.perft(&rules, 3));
+		// By symmetry, all corners of the square are alike.
+		assert!(divided[..4].iter().all(|&(_, n)| n == divided[0].1));
+	}
+
+	/// Compare the move generator using the liberties of the groups
+	/// to the one making every move
+	/// and the one working with matrices, depth by depth.
+	/// The counts under superko agree with those of `tools/perft.py`,
+	/// a separate flood fill implementation of the same conventions.
+	/// We know of no published counts to compare them to.
+	#[test]
+	fn perft_regression() {
+		let cases: &[(usize, &[usize])] = &[
+			(2, &[1, 5, 21, 68, 156, 316]),
+			(3, &[1, 10, 91, 738, 5281]),
+			(4, &[1, 17, 273, 4112]),
+		];
+		for &(size, counts) in cases {
+			for superko in &[true, false] {
+				let rules = Rules {
+					superko: *superko,
+					..Rules::default()
+				};
+				let square = Square::new(size);
+				let root =
+					GameNode::new(GameState::new(&square));
+				for (depth, &count) in counts.iter().enumerate()
+				{
+					let fast = root.perft(&rules, depth);
+					let slow = root.perft_with(
+						&rules,
+						depth,
+						&legal_by_trial,
+					);
+					assert_eq!(fast, slow);
+					let matrix = root.perft_with(
+						&rules,
+						depth,
+						&legal_by_matrix,
+					);
+					assert_eq!(fast, matrix);
+					if *superko {
+						assert_eq!(
+							(size, depth, fast),
+							(size, depth, count)
+						);
+					}
+				}
+			}
+		}
+	}
 }
@@ -1,4 +1,3 @@
 pub mod board;
 pub mod game;
-pub mod rules;
 pub mod util;
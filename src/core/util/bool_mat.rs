@@ -1,207 +1,260 @@
-//! Matrices are at the heart of finite-dimensional linear algebra
-//! and we, too, will use them to perform the calculations we want to do.
-//! While any matrix can be considered to be a vector,
-//! matrices have the additional structure of multiplication
-//! which allows us to elegantly chain linear transformations.
-//! Non-square matrices allow us to change the underlying set
-//! when applying them to characteristic functions.
-
-use self::indexer::Indexer;
-use core::util::bool_vec::BoolVec;
-use core::util::indexer;
-use std::ops::{Index, IndexMut, Mul};
+//! Boolean matrices are binary relations
+//! between the index sets of their rows and columns.
+//! Multiplication composes relations
+//! and evaluation takes the image of a set under a relation,
+//! so the usual linear algebra over the two-element semi-ring
+//! answers questions about reachability.
 
-// TODO: There is some renaming to be done.
-//       * s/eval/apply/ ?
+use core::util::bool_vec::BoolVec;
+use core::util::indexer::{Indexer, Product};
+use core::util::linear_map::LinearMap;
+use core::util::mat::Mat;
 
 /// A matrix with values in `bool`, the two-element semi-ring.
-/// Since not all matrices represent endomorphisms,
-/// rows and columns each have their own `Indexer`.
-#[derive(Debug, Eq, PartialEq, Clone)]
-pub struct BoolMat<J: Indexer, K: Indexer> {
-	rows: J,
-	columns: K,
-	contents: BoolVec<indexer::Rect>,
-}
+pub type BoolMat<J, K> = Mat<bool, J, K>;
 
-impl<J, K> Index<(J::Index, K::Index)> for BoolMat<J, K>
+impl<J, K> Mat<bool, J, K>
 where
 	J: Indexer,
 	K: Indexer,
 {
-	type Output = bool;
+	/// Create a new Boolean matrix with all entries unset.
+	pub fn falses(rows: J, columns: K) -> Self {
+		Mat::zeros(rows, columns)
+	}
 
-	fn index(&self, (j, k): (J::Index, K::Index)) -> &Self::Output {
-		let i = (self.rows.to_num(j), self.columns.to_num(k));
-		&self.contents[i]
+	/// Create a new Boolean matrix with all entries set.
+	pub fn trues(rows: J, columns: K) -> Self {
+		let rect = Product::new(rows.range(), columns.range());
+		let data = BoolVec::trues(rect).as_slice().to_vec();
+		Mat::from_data(rows, columns, data)
 	}
-}
 
-impl<J, K> IndexMut<(J::Index, K::Index)> for BoolMat<J, K>
-where
-	J: Indexer,
-	K: Indexer,
-{
-	fn index_mut(
-		&mut self,
-		(j, k): (J::Index, K::Index),
-	) -> &mut Self::Output {
-		let i = (self.rows.to_num(j), self.columns.to_num(k));
-		&mut self.contents[i]
+	/// Intersect two matrices considered as relations.
+	/// Their union is the sum.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::util::bool_mat::BoolMat;
+	/// let mut a = BoolMat::falses(2, 2);
+	/// a[(0, 1)] = true;
+	/// a[(1, 1)] = true;
+	/// let b = a.transpose();
+	/// let both = a.intersection(&b);
+	/// assert!(both[(1, 1)]);
+	/// assert!(!both[(0, 1)]);
+	/// assert!((&a + &b)[(1, 0)]);
+	/// ```
+	pub fn intersection(&self, other: &Self) -> Self {
+		assert_eq!(self.rows(), other.rows());
+		assert_eq!(self.columns(), other.columns());
+		let data = self
+			.as_slice()
+			.iter()
+			.zip(other.as_slice())
+			.map(|(&a, &b)| a && b)
+			.collect();
+		let rows = self.rows().clone();
+		let columns = self.columns().clone();
+		Mat::from_data(rows, columns, data)
 	}
 }
 
-impl<J, K> BoolMat<J, K>
+impl<J, K> LinearMap<J, K> for Mat<bool, J, K>
 where
 	J: Indexer,
 	K: Indexer,
 {
-	/// Create a new Boolean matrix with all entries unset.
-	pub fn falses(rows: J, columns: K) -> Self {
-		let rect = indexer::Rect::new(rows.range(), columns.range());
-		let contents = BoolVec::falses(rect);
-		BoolMat {
-			rows,
-			columns,
-			contents,
+	fn from_entries<E>(rows: J, columns: K, entries: E) -> Self
+	where
+		E: IntoIterator<Item = (J::Index, K::Index)>,
+	{
+		let mut res = BoolMat::falses(rows, columns);
+		for i in entries {
+			res[i] = true;
 		}
+		res
 	}
 
-	/// Create a new Boolean matrix with all entries set.
-	pub fn trues(rows: J, columns: K) -> Self {
-		let rect = indexer::Rect::new(rows.range(), columns.range());
-		let contents = BoolVec::trues(rect);
-		BoolMat {
-			rows,
-			columns,
-			contents,
-		}
+	fn rows(&self) -> &J {
+		Mat::rows(self)
 	}
 
-	/// Evaluate the matrix on a vector,
-	/// which is considered as a column vector.
-	pub fn eval(&self, v: &BoolVec<K>) -> BoolVec<J> {
-		let matrix = self * BoolMat::column(v);
-		let indexer = matrix.rows;
-		matrix.contents.reindex(indexer)
+	fn columns(&self) -> &K {
+		Mat::columns(self)
 	}
-}
 
-impl<I> BoolMat<I, ()>
-where
-	I: Indexer,
-{
-	/// Take a vector and write it as column,
-	/// i.e. a matrix where the columns are indexed by the unit type.
-	fn column(vec: &BoolVec<I>) -> Self {
-		let rows = vec.indexer().clone();
-		let columns = ();
-		let rect = indexer::Rect::new(rows.range(), 1);
-		let contents = vec.clone().reindex(rect);
-		BoolMat {
-			rows,
-			columns,
-			contents,
-		}
+	fn entry(&self, j: J::Index, k: K::Index) -> bool {
+		self[(j, k)]
 	}
-}
 
-impl<I> BoolMat<I, I>
-where
-	I: Indexer,
-{
-	/// Create a diagonal matrix
-	/// whose diagonal entries are given by a vector.
-	pub fn from_diag(diag: &BoolVec<I>) -> Self {
-		let indexer = diag.indexer();
-		let rows = indexer.clone();
-		let columns = indexer.clone();
-		let mut res = BoolMat::falses(rows, columns);
-		let len = indexer.range();
-		for i in 0..len {
-			res.contents[(i, i)] = diag[indexer.to_index(i)];
-		}
-		res
+	fn entries(&self) -> Vec<(J::Index, K::Index)> {
+		let rows = self.rows();
+		let columns = self.columns();
+		let width = columns.range();
+		let set =
+			self.as_slice().iter().enumerate().filter(|&(_, &b)| b);
+		set.map(|(n, _)| {
+			(rows.to_index(n / width), columns.to_index(n % width))
+		})
+		.collect()
+	}
+
+	fn eval(&self, v: &BoolVec<K>) -> BoolVec<J> {
+		Mat::eval(self, v)
+	}
+
+	fn mul_right<L: Indexer>(
+		&self,
+		other: &BoolMat<K, L>,
+	) -> BoolMat<J, L> {
+		self * other
+	}
+
+	fn mul_left<L: Indexer>(&self, other: &BoolMat<L, J>) -> BoolMat<L, K> {
+		other * self
 	}
 
-	/// Create the identity matrix to a given `Indexer`.
-	pub fn id_matrix(indexer: I) -> Self {
-		BoolMat::from_diag(&BoolVec::trues(indexer))
+	fn to_dense(&self) -> BoolMat<J, K> {
+		self.clone()
 	}
 }
 
-// TODO: Implement consuming versions.
-impl<'now, J, K, L> Mul<&'now BoolMat<K, L>> for &'now BoolMat<J, K>
+impl<I> Mat<bool, I, I>
 where
-	J: Indexer,
-	K: Indexer,
-	L: Indexer,
+	I: Indexer,
 {
-	type Output = BoolMat<J, L>;
-
-	fn mul(self, other: &BoolMat<K, L>) -> Self::Output {
-		assert_eq!(self.columns, other.rows);
-		let len = self.columns.range();
-		let rows = self.rows.clone();
-		let columns = other.columns.clone();
-		let height = rows.range();
-		let width = columns.range();
-		let entry = |j, l| {
-			(0..len).map(|k| {
-				self.contents[(j, k)] & other.contents[(k, l)]
-			}).any(|b| b)
-		};
-		let mut data = Vec::with_capacity(height * width);
-		for j in 0..height {
-			for l in 0..width {
-				data.push(entry(j, l));
+	/// The reflexive-transitive closure of a relation,
+	/// i.e. which points can be reached from which
+	/// in any number of steps, including none.
+	/// Calculated by repeated squaring
+	/// until nothing changes any more.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Rect};
+	/// let rect = Rect::new(6, 4);
+	/// let reach = rect.adjacencies().closure();
+	/// assert!(reach[((0, 0), (5, 3))]);
+	/// ```
+	pub fn closure(&self) -> Self {
+		let id = Mat::id_matrix(self.rows().clone());
+		let mut res = self + &id;
+		loop {
+			let next = &res * &res;
+			if next == res {
+				return res;
 			}
-		}
-		let rect = indexer::Rect::new(height, width);
-		let contents = BoolVec::from_data(data, rect);
-		BoolMat {
-			rows,
-			columns,
-			contents,
+			res = next;
 		}
 	}
-}
-
-impl<'now, J, K, L> Mul<BoolMat<K, L>> for &'now BoolMat<J, K>
-where
-	J: Indexer,
-	K: Indexer,
-	L: Indexer,
-{
-	type Output = BoolMat<J, L>;
 
-	fn mul(self, other: BoolMat<K, L>) -> Self::Output {
-		self * &other
+	/// The reflexive-transitive closure
+	/// of the relation restricted to a subset.
+	/// Only paths running entirely within the subset count,
+	/// and points outside the subset are not related to anything,
+	/// not even themselves.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Rect};
+	/// # use gorrosion::core::util::bool_vec::BoolVec;
+	/// # use gorrosion::core::util::indexer;
+	/// let rect = Rect::new(1, 3);
+	/// let mut ends = BoolVec::trues(indexer::Rect::new(1, 3));
+	/// ends[(0, 1)] = false;
+	/// let reach = rect.adjacencies().closure_restricted(&ends);
+	/// assert!(reach[((0, 0), (0, 0))]);
+	/// assert!(!reach[((0, 0), (0, 2))]);
+	/// assert!(!reach[((0, 1), (0, 1))]);
+	/// ```
+	pub fn closure_restricted(&self, subset: &BoolVec<I>) -> Self {
+		let diag = &Mat::from_diag(subset);
+		let restricted = diag * self * diag;
+		diag * restricted.closure() * diag
 	}
 }
 
-impl<'now, J, K, L> Mul<&'now BoolMat<K, L>> for BoolMat<J, K>
-where
-	J: Indexer,
-	K: Indexer,
-	L: Indexer,
-{
-	type Output = BoolMat<J, L>;
+#[cfg(test)]
+mod tests {
+	use super::BoolMat;
+	use core::board::{Board, Rect};
+	use core::util::bool_vec::BoolVec;
+	use core::util::indexer;
 
-	fn mul(self, other: &'now BoolMat<K, L>) -> Self::Output {
-		&self * other
+	#[test]
+	fn doc_tests() {
+		let mut a = BoolMat::falses(2, 2);
+		a[(0, 1)] = true;
+		a[(1, 1)] = true;
+		let b = a.transpose();
+		let both = a.intersection(&b);
+		assert!(both[(1, 1)]);
+		assert!(!both[(0, 1)]);
+		assert!((&a + &b)[(1, 0)]);
+		let rect = Rect::new(6, 4);
+		let reach = rect.adjacencies().closure();
+		assert!(reach[((0, 0), (5, 3))]);
+		let rect = Rect::new(1, 3);
+		let mut ends = BoolVec::trues(indexer::Rect::new(1, 3));
+		ends[(0, 1)] = false;
+		let reach = rect.adjacencies().closure_restricted(&ends);
+		assert!(reach[((0, 0), (0, 0))]);
+		assert!(!reach[((0, 0), (0, 2))]);
+		assert!(!reach[((0, 1), (0, 1))]);
 	}
-}
 
-impl<J, K, L> Mul<BoolMat<K, L>> for BoolMat<J, K>
-where
-	J: Indexer,
-	K: Indexer,
-	L: Indexer,
-{
-	type Output = BoolMat<J, L>;
+	#[test]
+	fn closure_rect() {
+		let rect = Rect::new(5, 7);
+		let adj = rect.adjacencies();
+		let indexer = indexer::Rect::new(5, 7);
+		assert_eq!(
+			adj.closure(),
+			BoolMat::trues(indexer.clone(), indexer)
+		);
+		// The closure only needs as many steps as the diameter.
+		assert_eq!(adj.closure(), adj.pow(4 + 6));
+		assert!(adj.closure() != adj.pow(4 + 5));
+	}
 
-	fn mul(self, other: BoolMat<K, L>) -> Self::Output {
-		&self * &other
+	#[test]
+	fn closure_disconnected() {
+		// Two paths 0 - 1 - 2 and 3 - 4 without a connection.
+		let mut adj = BoolMat::falses(5, 5);
+		for &(a, b) in &[(0, 1), (1, 2), (3, 4)] {
+			adj[(a, b)] = true;
+			adj[(b, a)] = true;
+		}
+		let reach = adj.closure();
+		assert!(reach[(0, 2)]);
+		assert!(reach[(4, 3)]);
+		assert!(reach[(3, 3)]);
+		assert!(!reach[(2, 3)]);
+		assert!(!reach[(4, 0)]);
+		assert_eq!(reach.closure(), reach);
+	}
+
+	#[test]
+	fn closure_restricted_rect() {
+		// Cut a 3x3 board in half along the middle column.
+		let rect = Rect::new(3, 3);
+		let indexer = indexer::Rect::new(3, 3);
+		let mut subset = BoolVec::trues(indexer);
+		for j in 0..3 {
+			subset[(j, 1)] = false;
+		}
+		let reach = rect.adjacencies().closure_restricted(&subset);
+		assert!(reach[((0, 0), (2, 0))]);
+		assert!(reach[((2, 2), (0, 2))]);
+		assert!(!reach[((0, 0), (0, 2))]);
+		assert!(!reach[((1, 1), (1, 1))]);
+		// Without the cut, everything is connected.
+		let all = BoolVec::trues(subset.indexer().clone());
+		let reach = rect.adjacencies().closure_restricted(&all);
+		assert_eq!(reach, rect.adjacencies().closure());
 	}
 }
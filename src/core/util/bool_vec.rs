@@ -4,43 +4,18 @@
 //! to be implemented as simple linear algebra over the two-element semi-ring.
 
 use core::util::indexer::Indexer;
-use std::ops::{BitAnd, BitOr, Index, IndexMut, Not};
+use core::util::kernels;
+use core::util::vec::Vec;
+use std::ops::{BitAnd, BitOr, Not};
 
 /// A vector with values in `bool`, the two-element semi-ring.
-/// For convenience and a poor emulation of type-checking
-/// we do not index these over integers directly
-/// but use an `Indexer`.
-#[derive(Eq, PartialEq, Debug, Clone)]
-pub struct BoolVec<I: Indexer> {
-	data: Vec<bool>,
-	indexer: I,
-}
-
-impl<I: Indexer> Index<I::Index> for BoolVec<I> {
-	type Output = bool;
-
-	fn index(&self, i: I::Index) -> &Self::Output {
-		&self.data[self.indexer.to_num(i)]
-	}
-}
-
-impl<I: Indexer> IndexMut<I::Index> for BoolVec<I> {
-	fn index_mut(&mut self, i: I::Index) -> &mut Self::Output {
-		&mut self.data[self.indexer.to_num(i)]
-	}
-}
+pub type BoolVec<I> = Vec<bool, I>;
 
 // TODO: Allow iteration over set / true positions?
-impl<I> BoolVec<I>
+impl<I> Vec<bool, I>
 where
 	I: Indexer,
 {
-	/// Wrap an existing Vec<bool> into a Boolen vector.
-	pub fn from_data(data: Vec<bool>, indexer: I) -> BoolVec<I> {
-		assert_eq!(data.len(), indexer.range());
-		BoolVec { data, indexer }
-	}
-
 	/// Create a new Boolean vector with all positions being unset.
 	///
 	/// # Examples
@@ -50,13 +25,11 @@ where
 	/// let indexer = 17;
 	/// let falses = BoolVec::falses(indexer);
 	/// for i in 0..17 {
-	///	assert_eq!(falses[i], false);
+	///	assert!(!falses[i]);
 	/// }
 	/// ```
 	pub fn falses(indexer: I) -> Self {
-		let size = indexer.range();
-		let data = vec![false; size];
-		BoolVec { data, indexer }
+		Vec::zeros(indexer)
 	}
 
 	/// Create a new Boolean vector with all positions being set.
@@ -68,44 +41,11 @@ where
 	/// let indexer = 23;
 	/// let trues = BoolVec::trues(indexer);
 	/// for i in 0..23 {
-	///	assert_eq!(trues[i], true);
+	///	assert!(trues[i]);
 	/// }
 	/// ```
 	pub fn trues(indexer: I) -> Self {
-		let size = indexer.range();
-		let data = vec![true; size];
-		BoolVec { data, indexer }
-	}
-
-	/// Consider a vector as a map into the Booleans
-	/// and apply a unary operation pointwise.
-	// TODO: Find common abstraction of bit_map_unary and bit_map_binary.
-	// TODO: Implement consuming version.
-	fn bit_map_unary<F: Fn(bool) -> bool>(&self, op: F) -> Self {
-		let indexer = self.indexer.clone();
-		let size = self.data.len();
-		let mut data = Vec::with_capacity(size);
-		for i in 0..size {
-			data.push(op(self.data[i]));
-		}
-		BoolVec { data, indexer }
-	}
-
-	/// Consider two vectors as maps into the Booleans
-	/// and apply a binary operation pointwise.
-	fn bit_map_binary<F: Fn(bool, bool) -> bool>(
-		&self,
-		other: &Self,
-		op: F,
-	) -> Self {
-		assert_eq!(self.indexer, other.indexer);
-		let indexer = self.indexer.clone();
-		let size = self.data.len();
-		let mut data = Vec::with_capacity(size);
-		for i in 0..size {
-			data.push(op(self.data[i], other.data[i]));
-		}
-		BoolVec { data, indexer }
+		Vec::ones(indexer)
 	}
 
 	/// Intersect two vectors considered as sets.
@@ -156,64 +96,59 @@ where
 		!self
 	}
 
-	/// Change the way the vector is indexed.
+	/// The number of set positions,
+	/// i.e. the cardinality of the vector considered as set.
 	///
 	/// # Examples
 	///
 	/// ```
 	/// # use gorrosion::core::util::bool_vec::BoolVec;
-	/// # use gorrosion::core::util::indexer;
-	/// let rect = indexer::Rect::new(2, 3);
-	/// let num = 6;
-	/// let vec_rect = BoolVec::falses(rect);
-	/// let vec_num = BoolVec::falses(num);
-	/// assert_eq!(vec_num, vec_rect.reindex(num));
+	/// let a = BoolVec::from_data(vec![true, false, true], 3);
+	/// assert_eq!(a.count(), 2);
 	/// ```
-	pub fn reindex<J>(self, indexer: J) -> BoolVec<J>
-	where
-		J: Indexer,
-	{
-		assert_eq!(self.indexer.range(), indexer.range());
-		let data = self.data;
-		BoolVec { indexer, data }
-	}
-
-	/// Get a reference to the indexer.
-	pub fn indexer(&self) -> &I {
-		&self.indexer
+	pub fn count(&self) -> usize {
+		kernels::count(self.as_slice())
 	}
 }
 
-impl<'now, I> BitAnd for &'now BoolVec<I>
+impl<I> BitAnd for &BoolVec<I>
 where
 	I: Indexer,
 {
 	type Output = BoolVec<I>;
 
 	fn bitand(self, other: Self) -> Self::Output {
-		BoolVec::bit_map_binary(self, other, BitAnd::bitand)
+		assert_eq!(self.indexer(), other.indexer());
+		let mut res = self.clone();
+		kernels::and_assign(res.as_mut_slice(), other.as_slice());
+		res
 	}
 }
 
-impl<'now, I> BitOr for &'now BoolVec<I>
+impl<I> BitOr for &BoolVec<I>
 where
 	I: Indexer,
 {
 	type Output = BoolVec<I>;
 
 	fn bitor(self, other: Self) -> Self::Output {
-		BoolVec::bit_map_binary(self, other, BitOr::bitor)
+		assert_eq!(self.indexer(), other.indexer());
+		let mut res = self.clone();
+		kernels::or_assign(res.as_mut_slice(), other.as_slice());
+		res
 	}
 }
 
-impl<'now, I> Not for &'now BoolVec<I>
+impl<I> Not for &BoolVec<I>
 where
 	I: Indexer,
 {
 	type Output = BoolVec<I>;
 
 	fn not(self) -> Self::Output {
-		BoolVec::bit_map_unary(self, Not::not)
+		let mut res = self.clone();
+		kernels::not_assign(res.as_mut_slice());
+		res
 	}
 }
 
@@ -228,12 +163,12 @@ mod tests {
 		let indexer = 17;
 		let falses = BoolVec::falses(indexer);
 		for i in 0..17 {
-			assert_eq!(falses[i], false);
+			assert!(!falses[i]);
 		}
 		let indexer = 23;
 		let trues = BoolVec::trues(indexer);
 		for i in 0..23 {
-			assert_eq!(trues[i], true);
+			assert!(trues[i]);
 		}
 		let indexer = 3;
 		let a = BoolVec::from_data(vec![true, true, false], indexer);
@@ -2,7 +2,12 @@
 //! to allow for a clean abstraction of indexing vectors
 //! using these other types.
 
+use core::util::bool_vec::BoolVec;
+use std::borrow::Borrow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 
 /// The heart of the operation.
 /// Provides the methods for converting external indices
@@ -91,41 +96,609 @@ impl Indexer for usize {
 //       as this probably does not actually need to be tested.
 // LCOV_EXCL_START
 /// Index a rectangle, traversing it row by row.
+/// This is just the product of two integer ranges.
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Rect {
-	height: usize,
-	width: usize,
+	product: Product<usize, usize>,
 }
 // LCOV_EXCL_STOP
 
 impl Rect {
 	pub fn new(height: usize, width: usize) -> Rect {
-		Rect { height, width }
+		let product = Product::new(height, width);
+		Rect { product }
+	}
+
+	/// The number of rows.
+	pub fn height(&self) -> usize {
+		self.product.factors.0
+	}
+
+	/// The number of columns.
+	pub fn width(&self) -> usize {
+		self.product.factors.1
 	}
 }
 
 impl Indexer for Rect {
 	type Index = (usize, usize);
 
+	fn to_num(&self, i: Self::Index) -> usize {
+		self.product.to_num(i)
+	}
+
+	fn to_index(&self, n: usize) -> Self::Index {
+		self.product.to_index(n)
+	}
+
+	fn range(&self) -> usize {
+		self.product.range()
+	}
+
+	fn is_valid(&self, i: Self::Index) -> bool {
+		self.product.is_valid(i)
+	}
+}
+
+/// Index a cuboid, traversing it layer by layer,
+/// each layer row by row.
+/// This is just the product of three integer ranges.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Cuboid {
+	factors: (usize, usize, usize),
+}
+
+impl Cuboid {
+	pub fn new(depth: usize, height: usize, width: usize) -> Cuboid {
+		let factors = (depth, height, width);
+		Cuboid { factors }
+	}
+
+	/// The number of layers.
+	pub fn depth(&self) -> usize {
+		self.factors.0
+	}
+
+	/// The number of rows in each layer.
+	pub fn height(&self) -> usize {
+		self.factors.1
+	}
+
+	/// The number of columns in each layer.
+	pub fn width(&self) -> usize {
+		self.factors.2
+	}
+
+	/// The indexer of a single layer.
+	pub fn layer(&self) -> Rect {
+		Rect::new(self.height(), self.width())
+	}
+}
+
+impl Indexer for Cuboid {
+	type Index = (usize, usize, usize);
+
+	fn to_num(&self, i: Self::Index) -> usize {
+		self.factors.to_num(i)
+	}
+
+	fn to_index(&self, n: usize) -> Self::Index {
+		self.factors.to_index(n)
+	}
+
+	fn range(&self) -> usize {
+		self.factors.range()
+	}
+
+	fn is_valid(&self, i: Self::Index) -> bool {
+		self.factors.is_valid(i)
+	}
+}
+
+/// Index a hexagon made of hexagonal cells in axial coordinates.
+/// The cell `(q, r)` lies in row `r` and diagonal column `q`,
+/// with the central cell at `(0, 0)`.
+/// Its six neighbours are `(q ± 1, r)`, `(q, r ± 1)`,
+/// `(q + 1, r - 1)` and `(q - 1, r + 1)`.
+/// A hexagon with sides of length `side` contains exactly the cells
+/// for which `q`, `r` and `q + r` are all less than `side` in magnitude.
+/// The cells are traversed row by row.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hexagon {
+	side: usize,
+}
+
+impl Hexagon {
+	pub fn new(side: usize) -> Hexagon {
+		Hexagon { side }
+	}
+
+	/// The number of cells along each side.
+	pub fn side(&self) -> usize {
+		self.side
+	}
+
+	/// The largest magnitude of a valid coordinate.
+	fn radius(&self) -> isize {
+		self.side as isize - 1
+	}
+
+	/// The internal index of the first cell in the given row,
+	/// counting rows from the top starting at zero.
+	fn row_start(&self, row: usize) -> usize {
+		// Up to the middle, the rows grow by one cell each,
+		// afterwards they shrink symmetrically.
+		let upper = |rows: usize| {
+			rows * self.side + rows * rows / 2 - rows / 2
+		};
+		let middle = self.side.saturating_sub(1);
+		if row <= middle {
+			upper(row)
+		} else {
+			self.range() - upper(2 * middle + 1 - row)
+		}
+	}
+}
+
+impl Indexer for Hexagon {
+	type Index = (isize, isize);
+
+	fn to_num(&self, i: Self::Index) -> usize {
+		assert!(self.is_valid(i));
+		let (q, r) = i;
+		let first = (-self.radius()).max(-self.radius() - r);
+		self.row_start((r + self.radius()) as usize)
+			+ (q - first) as usize
+	}
+
+	fn to_index(&self, n: usize) -> Self::Index {
+		assert!(self.in_range(n));
+		let mut row = 0;
+		while self.row_start(row + 1) <= n {
+			row += 1;
+		}
+		let r = row as isize - self.radius();
+		let first = (-self.radius()).max(-self.radius() - r);
+		(first + (n - self.row_start(row)) as isize, r)
+	}
+
+	fn range(&self) -> usize {
+		match self.side {
+			0 => 0,
+			side => 3 * side * (side - 1) + 1,
+		}
+	}
+
+	fn is_valid(&self, i: Self::Index) -> bool {
+		let (q, r) = i;
+		let radius = self.radius();
+		q.abs() <= radius
+			&& r.abs() <= radius && (q + r).abs() <= radius
+	}
+}
+
+/// Index a triangle made of triangular cells.
+/// Row `j`, counted from the apex starting at zero,
+/// consists of `2 j + 1` cells alternately pointing up and down.
+/// The cell `(j, k)` is the `k`-th cell in row `j`.
+/// It points up if `k` is even
+/// and then shares its lower edge with `(j + 1, k + 1)`,
+/// otherwise it shares its upper edge with `(j - 1, k - 1)`.
+/// The cells are traversed row by row.
+///
+/// Unlike `Hexagon`, this does not use axial coordinates:
+/// Triangular cells come in two orientations,
+/// which axial coordinates would need an extra component to tell apart.
+/// Rows and offsets make do with two numbers,
+/// and as the first `j` rows hold exactly `j²` cells,
+/// the internal index of `(j, k)` is simply `j² + k`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Triangle {
+	side: usize,
+}
+
+impl Triangle {
+	pub fn new(side: usize) -> Triangle {
+		Triangle { side }
+	}
+
+	/// The number of cells along each side pointing towards it.
+	pub fn side(&self) -> usize {
+		self.side
+	}
+}
+
+impl Indexer for Triangle {
+	type Index = (usize, usize);
+
 	fn to_num(&self, i: Self::Index) -> usize {
 		assert!(self.is_valid(i));
 		let (j, k) = i;
-		j * self.width + k
+		j * j + k
+	}
+
+	fn to_index(&self, n: usize) -> Self::Index {
+		assert!(self.in_range(n));
+		let j = n.isqrt();
+		(j, n - j * j)
+	}
+
+	fn range(&self) -> usize {
+		self.side * self.side
+	}
+
+	fn is_valid(&self, i: Self::Index) -> bool {
+		let (j, k) = i;
+		j < self.side && k <= 2 * j
+	}
+}
+
+/// Index the Cartesian product of finitely many index sets,
+/// given as tuple of indexers,
+/// traversing it lexicographically.
+macro_rules! tuple_indexer {
+	($($factor:ident: $k:tt),+) => {
+		impl<$($factor),+> Indexer for ($($factor,)+)
+		where
+			$($factor: Indexer),+
+		{
+			type Index = ($($factor::Index,)+);
+
+			fn to_num(&self, i: Self::Index) -> usize {
+				let mut n = 0;
+				$(n = n * self.$k.range() + self.$k.to_num(i.$k);)+
+				n
+			}
+
+			fn to_index(&self, n: usize) -> Self::Index {
+				assert!(self.in_range(n));
+				let mut stride = self.range();
+				($({
+					let range = self.$k.range();
+					stride /= range;
+					self.$k.to_index(n / stride % range)
+				},)+)
+			}
+
+			fn range(&self) -> usize {
+				1 $(* self.$k.range())+
+			}
+
+			fn is_valid(&self, i: Self::Index) -> bool {
+				true $(& self.$k.is_valid(i.$k))+
+			}
+		}
+	};
+}
+
+tuple_indexer!(A: 0);
+tuple_indexer!(A: 0, B: 1);
+tuple_indexer!(A: 0, B: 1, C: 2);
+tuple_indexer!(A: 0, B: 1, C: 2, D: 3);
+tuple_indexer!(A: 0, B: 1, C: 2, D: 3, E: 4);
+tuple_indexer!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+
+/// Index the Cartesian product of two index sets,
+/// traversing it lexicographically.
+/// For more than two factors, use a tuple of indexers.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Product<A: Indexer, B: Indexer> {
+	factors: (A, B),
+}
+
+impl<A, B> Product<A, B>
+where
+	A: Indexer,
+	B: Indexer,
+{
+	pub fn new(first: A, second: B) -> Product<A, B> {
+		let factors = (first, second);
+		Product { factors }
+	}
+
+	/// The indexer of the first factor.
+	pub fn first(&self) -> &A {
+		&self.factors.0
+	}
+
+	/// The indexer of the second factor.
+	pub fn second(&self) -> &B {
+		&self.factors.1
+	}
+}
+
+impl<A, B> Indexer for Product<A, B>
+where
+	A: Indexer,
+	B: Indexer,
+{
+	type Index = (A::Index, B::Index);
+
+	fn to_num(&self, i: Self::Index) -> usize {
+		self.factors.to_num(i)
+	}
+
+	fn to_index(&self, n: usize) -> Self::Index {
+		self.factors.to_index(n)
+	}
+
+	fn range(&self) -> usize {
+		self.factors.range()
+	}
+
+	fn is_valid(&self, i: Self::Index) -> bool {
+		self.factors.is_valid(i)
+	}
+}
+
+/// An element of a disjoint union:
+/// It either stems from the left or from the right summand.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Either<A, B> {
+	Left(A),
+	Right(B),
+}
+
+/// Index the disjoint union of two index sets,
+/// first all of the left one, then all of the right one.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Sum<A: Indexer, B: Indexer> {
+	left: A,
+	right: B,
+}
+
+impl<A, B> Sum<A, B>
+where
+	A: Indexer,
+	B: Indexer,
+{
+	pub fn new(left: A, right: B) -> Sum<A, B> {
+		Sum { left, right }
+	}
+
+	/// The indexer of the left summand.
+	pub fn left(&self) -> &A {
+		&self.left
+	}
+
+	/// The indexer of the right summand.
+	pub fn right(&self) -> &B {
+		&self.right
+	}
+}
+
+impl<A, B> Indexer for Sum<A, B>
+where
+	A: Indexer,
+	B: Indexer,
+{
+	type Index = Either<A::Index, B::Index>;
+
+	fn to_num(&self, i: Self::Index) -> usize {
+		match i {
+			Either::Left(a) => self.left.to_num(a),
+			Either::Right(b) => {
+				self.left.range() + self.right.to_num(b)
+			}
+		}
+	}
+
+	fn to_index(&self, n: usize) -> Self::Index {
+		assert!(self.in_range(n));
+		let offset = self.left.range();
+		if n < offset {
+			Either::Left(self.left.to_index(n))
+		} else {
+			Either::Right(self.right.to_index(n - offset))
+		}
+	}
+
+	fn range(&self) -> usize {
+		self.left.range() + self.right.range()
+	}
+
+	fn is_valid(&self, i: Self::Index) -> bool {
+		match i {
+			Either::Left(a) => self.left.is_valid(a),
+			Either::Right(b) => self.right.is_valid(b),
+		}
+	}
+}
+
+/// Index only those indices of a parent indexer
+/// that are set in a mask,
+/// numbering them consecutively in the order of the parent.
+/// This allows for irregular shapes,
+/// e.g. an L-shaped board or a board with holes,
+/// without wasting any internal indices.
+///
+/// # Examples
+///
+/// ```
+/// # use gorrosion::core::util::bool_vec::BoolVec;
+/// # use gorrosion::core::util::indexer::{Indexer, Masked, Rect};
+/// // An L-shape made from three of the four points of a square.
+/// let mut mask = BoolVec::trues(Rect::new(2, 2));
+/// mask[(0, 1)] = false;
+/// let l = Masked::new(&mask);
+/// assert_eq!(l.range(), 3);
+/// assert_eq!(l.to_num((1, 0)), 1);
+/// assert!(!l.is_valid((0, 1)));
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Masked<I: Indexer> {
+	parent: I,
+	// For every internal index, the parent's internal index.
+	nums: Vec<usize>,
+	// For every internal index of the parent, our internal index.
+	compact: Vec<Option<usize>>,
+}
+
+impl<I> Masked<I>
+where
+	I: Indexer,
+{
+	pub fn new(mask: &BoolVec<I>) -> Masked<I> {
+		let parent = mask.indexer().clone();
+		let mut nums = Vec::new();
+		let mut compact = Vec::with_capacity(parent.range());
+		for (n, &b) in mask.as_slice().iter().enumerate() {
+			if b {
+				compact.push(Some(nums.len()));
+				nums.push(n);
+			} else {
+				compact.push(None);
+			}
+		}
+		Masked {
+			parent,
+			nums,
+			compact,
+		}
+	}
+
+	/// The indexer we select our indices from.
+	pub fn parent(&self) -> &I {
+		&self.parent
+	}
+
+	/// The mask as a vector over the parent.
+	pub fn mask(&self) -> BoolVec<I> {
+		let data = self.compact.iter().map(Option::is_some).collect();
+		BoolVec::from_data(data, self.parent.clone())
+	}
+}
+
+impl<I> Indexer for Masked<I>
+where
+	I: Indexer,
+{
+	type Index = I::Index;
+
+	fn to_num(&self, i: Self::Index) -> usize {
+		assert!(self.is_valid(i));
+		self.compact[self.parent.to_num(i)].unwrap()
 	}
 
 	fn to_index(&self, n: usize) -> Self::Index {
 		assert!(self.in_range(n));
-		let j = n / self.width;
-		let k = n - j * self.width;
-		(j, k)
+		self.parent.to_index(self.nums[n])
 	}
 
 	fn range(&self) -> usize {
-		self.width * self.height
+		self.nums.len()
 	}
 
-	fn is_valid(&self, (j, k): Self::Index) -> bool {
-		(j < self.height) & (k < self.width)
+	fn is_valid(&self, i: Self::Index) -> bool {
+		self.parent.is_valid(i)
+			&& self.compact[self.parent.to_num(i)].is_some()
+	}
+}
+
+/// A stand-in for a label of a `Labelled` indexer.
+/// Labels themselves need not be `Copy`, so we index by these instead.
+/// A handle is only meaningful for the indexer that issued it,
+/// so it carries a fingerprint of the labels of that indexer.
+/// Equal indexers accept each other's handles,
+/// all others reject them.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub struct Handle {
+	num: usize,
+	owner: u64,
+}
+
+/// Index an arbitrary finite set of labels,
+/// numbering them in the order they were given in.
+///
+/// # Examples
+///
+/// ```
+/// # use gorrosion::core::util::indexer::{Indexer, Labelled};
+/// let poles = Labelled::new(vec!["north-pole", "south-pole"]);
+/// let south = poles.handle("south-pole").unwrap();
+/// assert_eq!(poles.to_num(south), 1);
+/// assert_eq!(poles.label(south), &"south-pole");
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Labelled<K: Hash + Eq + Clone + Debug> {
+	labels: Vec<K>,
+	nums: HashMap<K, usize>,
+	fingerprint: u64,
+}
+
+impl<K> Labelled<K>
+where
+	K: Hash + Eq + Clone + Debug,
+{
+	/// Index the given labels, which must not repeat.
+	pub fn new<L>(labels: L) -> Labelled<K>
+	where
+		L: IntoIterator<Item = K>,
+	{
+		let labels: Vec<K> = labels.into_iter().collect();
+		let mut nums = HashMap::with_capacity(labels.len());
+		for (n, label) in labels.iter().enumerate() {
+			let old = nums.insert(label.clone(), n);
+			assert!(old.is_none(), "Repeated label {:?}", label);
+		}
+		let mut hasher = DefaultHasher::new();
+		labels.hash(&mut hasher);
+		let fingerprint = hasher.finish();
+		Labelled {
+			labels,
+			nums,
+			fingerprint,
+		}
+	}
+
+	fn issue(&self, num: usize) -> Handle {
+		let owner = self.fingerprint;
+		Handle { num, owner }
+	}
+
+	/// Look up the handle of a label, if it is known.
+	pub fn handle<Q>(&self, label: &Q) -> Option<Handle>
+	where
+		K: Borrow<Q>,
+		Q: Hash + Eq + ?Sized,
+	{
+		self.nums.get(label).map(|&n| self.issue(n))
+	}
+
+	/// Look up the label behind a handle.
+	pub fn label(&self, handle: Handle) -> &K {
+		assert!(self.is_valid(handle));
+		&self.labels[handle.num]
+	}
+
+	/// All labels in the order of their internal indices.
+	pub fn labels(&self) -> &[K] {
+		&self.labels
+	}
+}
+
+impl<K> Indexer for Labelled<K>
+where
+	K: Hash + Eq + Clone + Debug,
+{
+	type Index = Handle;
+
+	fn to_num(&self, handle: Self::Index) -> usize {
+		assert!(self.is_valid(handle));
+		handle.num
+	}
+
+	fn to_index(&self, n: usize) -> Self::Index {
+		assert!(self.in_range(n));
+		self.issue(n)
+	}
+
+	fn range(&self) -> usize {
+		self.labels.len()
+	}
+
+	fn is_valid(&self, handle: Self::Index) -> bool {
+		handle.owner == self.fingerprint && self.in_range(handle.num)
 	}
 }
 
@@ -194,4 +767,260 @@ mod tests {
 		let rect = Rect::new(h, w);
 		rect.to_index(rect.range());
 	} // LCOV_EXCL_LINE
+
+	#[test]
+	fn cuboid() {
+		use super::Cuboid;
+		let cuboid = Cuboid::new(2, 3, 4);
+		assert_eq!(cuboid.range(), 24);
+		assert_eq!(cuboid.to_index(0), (0, 0, 0));
+		assert_eq!(cuboid.to_index(5), (0, 1, 1));
+		assert_eq!(cuboid.to_num((1, 0, 0)), 12);
+		assert_eq!(cuboid.layer().range(), 12);
+		assert!(!cuboid.is_valid((2, 0, 0)));
+		assert!(!cuboid.is_valid((0, 0, 4)));
+	}
+
+	#[test]
+	fn hexagon() {
+		use super::Hexagon;
+		let hexagon = Hexagon::new(3);
+		assert_eq!(hexagon.range(), 19);
+		assert_eq!(hexagon.to_index(0), (0, -2));
+		assert_eq!(hexagon.to_index(3), (-1, -1));
+		assert_eq!(hexagon.to_num((0, 0)), 9);
+		assert_eq!(hexagon.to_index(18), (0, 2));
+		assert!(hexagon.is_valid((2, -2)));
+		assert!(!hexagon.is_valid((2, -3)));
+		assert!(!hexagon.is_valid((2, 1)));
+		for side in 0..6 {
+			let hexagon = Hexagon::new(side);
+			let radius = side as isize;
+			let mut n = 0;
+			for r in -radius..=radius {
+				for q in -radius..=radius {
+					if hexagon.is_valid((q, r)) {
+						assert_eq!(
+							hexagon.to_num((q, r)),
+							n
+						);
+						assert_eq!(
+							hexagon.to_index(n),
+							(q, r)
+						);
+						n += 1;
+					}
+				}
+			}
+			assert_eq!(n, hexagon.range());
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn hexagon_index_oob() {
+		use super::Hexagon;
+		Hexagon::new(3).to_num((-2, -1));
+	} // LCOV_EXCL_LINE
+
+	#[test]
+	fn triangle() {
+		use super::Triangle;
+		let triangle = Triangle::new(4);
+		assert_eq!(triangle.range(), 16);
+		assert_eq!(triangle.to_index(0), (0, 0));
+		assert_eq!(triangle.to_index(3), (1, 2));
+		assert_eq!(triangle.to_index(15), (3, 6));
+		assert_eq!(triangle.to_num((2, 0)), 4);
+		assert!(triangle.is_valid((2, 4)));
+		assert!(!triangle.is_valid((2, 5)));
+		assert!(!triangle.is_valid((4, 0)));
+		for n in 0..triangle.range() {
+			assert_eq!(triangle.to_num(triangle.to_index(n)), n);
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn triangle_num_oob() {
+		use super::Triangle;
+		let triangle = Triangle::new(3);
+		triangle.to_index(triangle.range());
+	} // LCOV_EXCL_LINE
+
+	#[test]
+	fn product() {
+		use super::{Product, Rect};
+		let product = Product::new(3, 4);
+		let rect = Rect::new(3, 4);
+		assert_eq!(product.range(), 12);
+		for n in 0..12 {
+			assert_eq!(product.to_index(n), rect.to_index(n));
+			assert_eq!(product.to_num(product.to_index(n)), n);
+		}
+		assert!(product.is_valid((2, 3)));
+		assert!(!product.is_valid((3, 0)));
+	}
+
+	#[test]
+	#[should_panic]
+	fn product_index_oob() {
+		use super::Product;
+		let product = Product::new(2, 3);
+		product.to_num((0, 3));
+	} // LCOV_EXCL_LINE
+
+	#[test]
+	fn sum() {
+		use super::Either::{Left, Right};
+		use super::{Rect, Sum};
+		let sum = Sum::new(Rect::new(2, 2), 3);
+		assert_eq!(sum.range(), 7);
+		assert_eq!(sum.to_num(Left((1, 0))), 2);
+		assert_eq!(sum.to_num(Right(0)), 4);
+		assert_eq!(sum.to_index(3), Left((1, 1)));
+		assert_eq!(sum.to_index(6), Right(2));
+		assert!(sum.is_valid(Right(2)));
+		assert!(!sum.is_valid(Right(3)));
+		assert!(!sum.is_valid(Left((0, 2))));
+	}
+
+	#[test]
+	#[should_panic]
+	fn sum_num_oob() {
+		use super::Sum;
+		let sum = Sum::new(2, 3);
+		sum.to_index(5);
+	} // LCOV_EXCL_LINE
+
+	#[test]
+	fn tuples() {
+		let single = (5,);
+		assert_eq!(single.range(), 5);
+		assert_eq!(single.to_index(3), (3,));
+		let triple = (2, 3, 4);
+		assert_eq!(triple.range(), 24);
+		assert_eq!(triple.to_num((0, 0, 1)), 1);
+		assert_eq!(triple.to_num((0, 1, 0)), 4);
+		assert_eq!(triple.to_num((1, 0, 0)), 12);
+		assert_eq!(triple.to_index(23), (1, 2, 3));
+		for n in 0..triple.range() {
+			assert_eq!(triple.to_num(triple.to_index(n)), n);
+		}
+		assert!(triple.is_valid((1, 2, 3)));
+		assert!(!triple.is_valid((1, 3, 3)));
+		// Factors may themselves be products.
+		use super::Rect;
+		let nested = (Rect::new(2, 2), (), 3);
+		assert_eq!(nested.range(), 12);
+		assert_eq!(nested.to_index(7), ((1, 0), (), 1));
+	}
+
+	#[test]
+	#[should_panic]
+	fn tuple_num_oob() {
+		let triple = (2, 3, 4);
+		triple.to_index(24);
+	} // LCOV_EXCL_LINE
+
+	#[test]
+	fn rect_dimensions() {
+		use super::Rect;
+		let rect = Rect::new(7, 17);
+		assert_eq!(rect.height(), 7);
+		assert_eq!(rect.width(), 17);
+	}
+
+	#[test]
+	fn masked() {
+		use super::{Masked, Rect};
+		use core::util::bool_vec::BoolVec;
+		// A 3x3 square with a hole in the middle.
+		let mut mask = BoolVec::trues(Rect::new(3, 3));
+		mask[(1, 1)] = false;
+		let ring = Masked::new(&mask);
+		assert_eq!(ring.range(), 8);
+		assert_eq!(ring.parent(), &Rect::new(3, 3));
+		assert_eq!(ring.mask(), mask);
+		assert_eq!(ring.to_num((1, 0)), 3);
+		assert_eq!(ring.to_num((1, 2)), 4);
+		assert_eq!(ring.to_index(4), (1, 2));
+		for n in 0..ring.range() {
+			assert_eq!(ring.to_num(ring.to_index(n)), n);
+		}
+		assert!(ring.is_valid((2, 2)));
+		assert!(!ring.is_valid((1, 1)));
+		assert!(!ring.is_valid((3, 0)));
+	}
+
+	#[test]
+	#[should_panic]
+	fn masked_index_oob() {
+		use super::{Masked, Rect};
+		use core::util::bool_vec::BoolVec;
+		let mut mask = BoolVec::trues(Rect::new(2, 2));
+		mask[(0, 0)] = false;
+		Masked::new(&mask).to_num((0, 0));
+	} // LCOV_EXCL_LINE
+
+	#[test]
+	fn masked_vectors() {
+		use super::{Masked, Rect};
+		use core::util::bool_vec::BoolVec;
+		let mut mask = BoolVec::falses(Rect::new(4, 4));
+		for k in 0..4 {
+			mask[(0, k)] = true;
+			mask[(k, 0)] = true;
+		}
+		let l = Masked::new(&mask);
+		let mut v = BoolVec::falses(l.clone());
+		v[(3, 0)] = true;
+		assert!(v[(3, 0)]);
+		assert_eq!(v.count(), 1);
+		assert_eq!(l.range(), 7);
+	}
+
+	#[test]
+	fn labelled() {
+		use super::Labelled;
+		let names = vec!["A".to_string(), "north-pole".to_string()];
+		let labelled = Labelled::new(names.clone());
+		assert_eq!(labelled.range(), 2);
+		assert_eq!(labelled.labels(), &names[..]);
+		let a = labelled.handle("A").unwrap();
+		let pole = labelled.handle("north-pole").unwrap();
+		assert_eq!(labelled.to_num(pole), 1);
+		assert_eq!(labelled.to_index(0), a);
+		assert_eq!(labelled.label(pole), "north-pole");
+		assert!(labelled.handle("south-pole").is_none());
+		assert!(labelled.is_valid(a));
+		// Handles of other indexers are rejected,
+		// even if they are in range.
+		let other =
+			Labelled::new(vec!["B".to_string(), "C".to_string()]);
+		assert!(!labelled.is_valid(other.handle("B").unwrap()));
+		let again = Labelled::new(names.clone());
+		assert!(labelled.is_valid(again.handle("A").unwrap()));
+		// Any hashable type will do.
+		let numbers = Labelled::new(vec![17, 4, 23]);
+		let h = numbers.handle(&4).unwrap();
+		assert_eq!(numbers.to_num(h), 1);
+		assert_eq!(numbers.label(numbers.to_index(2)), &23);
+	}
+
+	#[test]
+	#[should_panic]
+	fn labelled_repeated() {
+		use super::Labelled;
+		Labelled::new(vec!['a', 'b', 'a']);
+	} // LCOV_EXCL_LINE
+
+	#[test]
+	#[should_panic]
+	fn labelled_foreign_handle() {
+		use super::Labelled;
+		let big = Labelled::new(vec![1, 2, 3]);
+		let small = Labelled::new(vec![1]);
+		small.to_num(big.handle(&3).unwrap());
+	} // LCOV_EXCL_LINE
 }
@@ -0,0 +1,457 @@
+//! The innermost loops of Boolean linear algebra:
+//! pointwise logic on slices of Booleans and counting the set entries.
+//! Every `bool` occupies a byte holding either 0 or 1,
+//! so these loops are a natural fit for SIMD instructions.
+//! Note that this is byte-wise SIMD:
+//! Boolean vectors and matrices are not packed into words,
+//! so a 256 bit register holds 32 entries rather than 256,
+//! and the speed-up is bounded accordingly.
+//! With the `simd` feature enabled,
+//! the best instruction set available at runtime is used
+//! (AVX2 or SSE2 on x86_64), detected once at the first call;
+//! otherwise, or on other architectures,
+//! the portable implementations in `scalar` do the work.
+
+/// Set `acc` to the pointwise conjunction of `acc` and `other`.
+pub(crate) fn and_assign(acc: &mut [bool], other: &[bool]) {
+	assert_eq!(acc.len(), other.len());
+	#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+	{
+		let kernel = x86_64::detected().and_assign;
+		// SAFETY: The kernels were chosen to suit the running CPU.
+		return unsafe { kernel(acc, other) };
+	}
+	#[allow(unreachable_code)]
+	scalar::and_assign(acc, other)
+}
+
+/// Set `acc` to the pointwise disjunction of `acc` and `other`.
+pub(crate) fn or_assign(acc: &mut [bool], other: &[bool]) {
+	assert_eq!(acc.len(), other.len());
+	#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+	{
+		let kernel = x86_64::detected().or_assign;
+		// SAFETY: The kernels were chosen to suit the running CPU.
+		return unsafe { kernel(acc, other) };
+	}
+	#[allow(unreachable_code)]
+	scalar::or_assign(acc, other)
+}
+
+/// Negate every entry of `acc`.
+pub(crate) fn not_assign(acc: &mut [bool]) {
+	#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+	{
+		let kernel = x86_64::detected().not_assign;
+		// SAFETY: The kernels were chosen to suit the running CPU.
+		return unsafe { kernel(acc) };
+	}
+	#[allow(unreachable_code)]
+	scalar::not_assign(acc)
+}
+
+/// Count the set entries.
+pub(crate) fn count(data: &[bool]) -> usize {
+	#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+	{
+		let kernel = x86_64::detected().count;
+		// SAFETY: The kernels were chosen to suit the running CPU.
+		return unsafe { kernel(data) };
+	}
+	#[allow(unreachable_code)]
+	scalar::count(data)
+}
+
+/// The portable implementations,
+/// which also serve as reference for the accelerated ones.
+pub(crate) mod scalar {
+	pub(crate) fn and_assign(acc: &mut [bool], other: &[bool]) {
+		for (a, &b) in acc.iter_mut().zip(other) {
+			*a &= b;
+		}
+	}
+
+	pub(crate) fn or_assign(acc: &mut [bool], other: &[bool]) {
+		for (a, &b) in acc.iter_mut().zip(other) {
+			*a |= b;
+		}
+	}
+
+	pub(crate) fn not_assign(acc: &mut [bool]) {
+		for a in acc.iter_mut() {
+			*a = !*a;
+		}
+	}
+
+	pub(crate) fn count(data: &[bool]) -> usize {
+		data.iter().filter(|&&b| b).count()
+	}
+}
+
+/// The x86_64 implementations.
+/// They process as many full registers as possible
+/// and leave the remainder to the portable implementations.
+/// All of them only ever store bytes that are 0 or 1,
+/// so the slices remain valid slices of Booleans.
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+pub(crate) mod x86_64 {
+	use super::scalar;
+	use std::arch::x86_64::*;
+
+	use std::sync::OnceLock;
+
+	const SSE2_WIDTH: usize = 16;
+	const AVX2_WIDTH: usize = 32;
+
+	/// The kernels for one instruction set.
+	pub(crate) struct Kernels {
+		pub(crate) and_assign: unsafe fn(&mut [bool], &[bool]),
+		pub(crate) or_assign: unsafe fn(&mut [bool], &[bool]),
+		pub(crate) not_assign: unsafe fn(&mut [bool]),
+		pub(crate) count: unsafe fn(&[bool]) -> usize,
+	}
+
+	static SSE2: Kernels = Kernels {
+		and_assign: and_assign_sse2,
+		or_assign: or_assign_sse2,
+		not_assign: not_assign_sse2,
+		count: count_sse2,
+	};
+
+	static AVX2: Kernels = Kernels {
+		and_assign: and_assign_avx2,
+		or_assign: or_assign_avx2,
+		not_assign: not_assign_avx2,
+		count: count_avx2,
+	};
+
+	/// The kernels for the best instruction set the CPU supports.
+	/// Detection only happens on the first call,
+	/// as the kernels run in the innermost loops.
+	/// SSE2 is part of the x86_64 baseline.
+	pub(crate) fn detected() -> &'static Kernels {
+		static DETECTED: OnceLock<&'static Kernels> = OnceLock::new();
+		DETECTED.get_or_init(|| {
+			if is_x86_feature_detected!("avx2") {
+				&AVX2
+			} else {
+				&SSE2
+			}
+		})
+	}
+
+	/// Define a kernel combining `acc` with `other` pointwise,
+	/// a full register at a time.
+	macro_rules! binary_kernel {
+		($name:ident, $feature:tt, $width:ident, $reg:ty,
+		 $load:ident, $store:ident, $op:ident, $tail:path) => {
+			#[target_feature(enable = $feature)]
+			pub(crate) unsafe fn $name(
+				acc: &mut [bool],
+				other: &[bool],
+			) {
+				let n = acc.len() / $width * $width;
+				let a = acc.as_mut_ptr() as *mut $reg;
+				let b = other.as_ptr() as *const $reg;
+				for i in 0..n / $width {
+					let x = $load(a.add(i));
+					let y = $load(b.add(i));
+					$store(a.add(i), $op(x, y));
+				}
+				$tail(&mut acc[n..], &other[n..]);
+			}
+		};
+	}
+
+	/// Define a kernel negating `acc`,
+	/// a full register at a time,
+	/// by taking the exclusive or with the all-true vector.
+	macro_rules! not_kernel {
+		($name:ident, $feature:tt, $width:ident, $reg:ty,
+		 $load:ident, $store:ident, $xor:ident, $set1:ident) => {
+			#[target_feature(enable = $feature)]
+			pub(crate) unsafe fn $name(acc: &mut [bool]) {
+				let n = acc.len() / $width * $width;
+				let a = acc.as_mut_ptr() as *mut $reg;
+				let ones = $set1(1);
+				for i in 0..n / $width {
+					let x = $load(a.add(i));
+					$store(a.add(i), $xor(x, ones));
+				}
+				scalar::not_assign(&mut acc[n..]);
+			}
+		};
+	}
+
+	binary_kernel!(
+		and_assign_sse2,
+		"sse2",
+		SSE2_WIDTH,
+		__m128i,
+		_mm_loadu_si128,
+		_mm_storeu_si128,
+		_mm_and_si128,
+		scalar::and_assign
+	);
+	binary_kernel!(
+		and_assign_avx2,
+		"avx2",
+		AVX2_WIDTH,
+		__m256i,
+		_mm256_loadu_si256,
+		_mm256_storeu_si256,
+		_mm256_and_si256,
+		scalar::and_assign
+	);
+	binary_kernel!(
+		or_assign_sse2,
+		"sse2",
+		SSE2_WIDTH,
+		__m128i,
+		_mm_loadu_si128,
+		_mm_storeu_si128,
+		_mm_or_si128,
+		scalar::or_assign
+	);
+	binary_kernel!(
+		or_assign_avx2,
+		"avx2",
+		AVX2_WIDTH,
+		__m256i,
+		_mm256_loadu_si256,
+		_mm256_storeu_si256,
+		_mm256_or_si256,
+		scalar::or_assign
+	);
+	not_kernel!(
+		not_assign_sse2,
+		"sse2",
+		SSE2_WIDTH,
+		__m128i,
+		_mm_loadu_si128,
+		_mm_storeu_si128,
+		_mm_xor_si128,
+		_mm_set1_epi8
+	);
+	not_kernel!(
+		not_assign_avx2,
+		"avx2",
+		AVX2_WIDTH,
+		__m256i,
+		_mm256_loadu_si256,
+		_mm256_storeu_si256,
+		_mm256_xor_si256,
+		_mm256_set1_epi8
+	);
+
+	/// Sum up the bytes using the sum of absolute differences to zero,
+	/// which adds up eight bytes at a time into a 64 bit lane.
+	#[target_feature(enable = "sse2")]
+	pub(crate) unsafe fn count_sse2(data: &[bool]) -> usize {
+		let n = data.len() / SSE2_WIDTH * SSE2_WIDTH;
+		let p = data.as_ptr() as *const __m128i;
+		let zero = _mm_setzero_si128();
+		let mut sums = _mm_setzero_si128();
+		for i in 0..n / SSE2_WIDTH {
+			let x = _mm_loadu_si128(p.add(i));
+			sums = _mm_add_epi64(sums, _mm_sad_epu8(x, zero));
+		}
+		let mut lanes = [0u64; 2];
+		_mm_storeu_si128(lanes.as_mut_ptr() as *mut __m128i, sums);
+		let head = (lanes[0] + lanes[1]) as usize;
+		head + scalar::count(&data[n..])
+	}
+
+	/// Sum up the bytes using the sum of absolute differences to zero,
+	/// which adds up eight bytes at a time into a 64 bit lane.
+	#[target_feature(enable = "avx2")]
+	pub(crate) unsafe fn count_avx2(data: &[bool]) -> usize {
+		let n = data.len() / AVX2_WIDTH * AVX2_WIDTH;
+		let p = data.as_ptr() as *const __m256i;
+		let zero = _mm256_setzero_si256();
+		let mut sums = _mm256_setzero_si256();
+		for i in 0..n / AVX2_WIDTH {
+			let x = _mm256_loadu_si256(p.add(i));
+			sums = _mm256_add_epi64(sums, _mm256_sad_epu8(x, zero));
+		}
+		let mut lanes = [0u64; 4];
+		_mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, sums);
+		let head = lanes.iter().sum::<u64>() as usize;
+		head + scalar::count(&data[n..])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::scalar;
+	use core::util::bool_mat::BoolMat;
+
+	/// A small xorshift generator,
+	/// good enough to produce varied test data.
+	struct Random(u64);
+
+	impl Random {
+		fn next(&mut self) -> u64 {
+			self.0 ^= self.0 << 13;
+			self.0 ^= self.0 >> 7;
+			self.0 ^= self.0 << 17;
+			self.0
+		}
+
+		/// A vector whose entries are set with probability `1 / sparsity`.
+		fn bools(&mut self, len: usize, sparsity: u64) -> Vec<bool> {
+			(0..len).map(|_| self.next() % sparsity == 0).collect()
+		}
+
+		fn matrix(
+			&mut self,
+			height: usize,
+			width: usize,
+			sparsity: u64,
+		) -> BoolMat<usize, usize> {
+			let data = self.bools(height * width, sparsity);
+			BoolMat::from_data(height, width, data)
+		}
+	}
+
+	/// Lengths around all the register widths.
+	fn lengths() -> Vec<usize> {
+		let mut lengths: Vec<usize> = (0..70).collect();
+		lengths.extend(&[127, 128, 129, 361, 1000]);
+		lengths
+	}
+
+	type Binary = fn(&mut [bool], &[bool]);
+	type Unary = fn(&mut [bool]);
+	type Count = fn(&[bool]) -> usize;
+
+	fn check_binary(kernel: Binary, reference: Binary) {
+		let mut random = Random(0x9E37_79B9_7F4A_7C15);
+		for len in lengths() {
+			for &sparsity in &[1, 2, 5] {
+				let a = random.bools(len, sparsity);
+				let b = random.bools(len, sparsity);
+				let mut ours = a.clone();
+				let mut theirs = a;
+				kernel(&mut ours, &b);
+				reference(&mut theirs, &b);
+				assert_eq!(ours, theirs);
+			}
+		}
+	}
+
+	fn check_unary(kernel: Unary, reference: Unary) {
+		let mut random = Random(0x2545_F491_4F6C_DD1D);
+		for len in lengths() {
+			let a = random.bools(len, 2);
+			let mut ours = a.clone();
+			let mut theirs = a;
+			kernel(&mut ours);
+			reference(&mut theirs);
+			assert_eq!(ours, theirs);
+		}
+	}
+
+	fn check_count(kernel: Count, reference: Count) {
+		let mut random = Random(0xD1B5_4A32_D192_ED03);
+		for len in lengths() {
+			for &sparsity in &[1, 2, 5] {
+				let a = random.bools(len, sparsity);
+				assert_eq!(kernel(&a), reference(&a));
+			}
+		}
+	}
+
+	#[test]
+	fn dispatch() {
+		check_binary(super::and_assign, scalar::and_assign);
+		check_binary(super::or_assign, scalar::or_assign);
+		check_unary(super::not_assign, scalar::not_assign);
+		check_count(super::count, scalar::count);
+	}
+
+	#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+	#[test]
+	fn sse2() {
+		use super::x86_64;
+		// SAFETY: SSE2 is part of the x86_64 baseline.
+		check_binary(
+			|a, b| unsafe { x86_64::and_assign_sse2(a, b) },
+			scalar::and_assign,
+		);
+		check_binary(
+			|a, b| unsafe { x86_64::or_assign_sse2(a, b) },
+			scalar::or_assign,
+		);
+		check_unary(
+			|a| unsafe { x86_64::not_assign_sse2(a) },
+			scalar::not_assign,
+		);
+		check_count(
+			|a| unsafe { x86_64::count_sse2(a) },
+			scalar::count,
+		);
+	}
+
+	#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+	#[test]
+	fn avx2() {
+		use super::x86_64;
+		if !is_x86_feature_detected!("avx2") {
+			return;
+		}
+		// SAFETY: We just checked that the CPU supports AVX2.
+		check_binary(
+			|a, b| unsafe { x86_64::and_assign_avx2(a, b) },
+			scalar::and_assign,
+		);
+		check_binary(
+			|a, b| unsafe { x86_64::or_assign_avx2(a, b) },
+			scalar::or_assign,
+		);
+		check_unary(
+			|a| unsafe { x86_64::not_assign_avx2(a) },
+			scalar::not_assign,
+		);
+		check_count(
+			|a| unsafe { x86_64::count_avx2(a) },
+			scalar::count,
+		);
+	}
+
+	#[test]
+	fn matrix_product() {
+		// Compare the Boolean product with counting paths.
+		let mut random = Random(0xA076_1D64_78BD_642F);
+		let count = |b| if b { 1 } else { 0 };
+		for &(h, w, l) in &[(1, 1, 1), (7, 33, 65), (40, 17, 31)] {
+			for &sparsity in &[2, 5, 20] {
+				let a = random.matrix(h, w, sparsity);
+				let b = random.matrix(w, l, sparsity);
+				let product = &a * &b;
+				let paths = &a.map(count) * &b.map(count);
+				assert_eq!(
+					product,
+					paths.map(|n: usize| n > 0)
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn vector_operations() {
+		use core::util::bool_vec::BoolVec;
+		let mut random = Random(0xE703_7ED1_A0B4_28DB);
+		for len in lengths() {
+			let a = BoolVec::from_data(random.bools(len, 2), len);
+			let b = BoolVec::from_data(random.bools(len, 3), len);
+			for i in 0..len {
+				assert_eq!((&a & &b)[i], a[i] & b[i]);
+				assert_eq!((&a | &b)[i], a[i] | b[i]);
+				assert_eq!((!&a)[i], !a[i]);
+			}
+			let set = (0..len).filter(|&i| a[i]).count();
+			assert_eq!(a.count(), set);
+		}
+	}
+}
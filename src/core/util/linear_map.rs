@@ -0,0 +1,50 @@
+//! A Boolean matrix is only one way to write down a linear map
+//! between two free modules over the two-element semi-ring.
+//! For large but sparse relations, such as the adjacencies of big graphs,
+//! other representations are far more economical.
+//! This module provides the common interface
+//! so that calculations need not care about the representation.
+
+use core::util::bool_mat::BoolMat;
+use core::util::bool_vec::BoolVec;
+use core::util::indexer::Indexer;
+use std::fmt::Debug;
+
+/// A linear map from the vectors indexed by `K`
+/// to the vectors indexed by `J`,
+/// i.e. a matrix with rows indexed by `J` and columns indexed by `K`
+/// in some representation or other.
+pub trait LinearMap<J: Indexer, K: Indexer>: Eq + Debug + Clone {
+	/// Create the map from the list of its set entries.
+	fn from_entries<E>(rows: J, columns: K, entries: E) -> Self
+	where
+		E: IntoIterator<Item = (J::Index, K::Index)>;
+
+	/// The indexer of the rows.
+	fn rows(&self) -> &J;
+
+	/// The indexer of the columns.
+	fn columns(&self) -> &K;
+
+	/// Look up a single entry.
+	fn entry(&self, j: J::Index, k: K::Index) -> bool;
+
+	/// List all set entries, row by row.
+	fn entries(&self) -> Vec<(J::Index, K::Index)>;
+
+	/// Evaluate the map on a vector,
+	/// which is considered as a column vector.
+	fn eval(&self, v: &BoolVec<K>) -> BoolVec<J>;
+
+	/// Multiply with a dense matrix from the right,
+	/// i.e. calculate `self * other`.
+	fn mul_right<L: Indexer>(&self, other: &BoolMat<K, L>)
+		-> BoolMat<J, L>;
+
+	/// Multiply with a dense matrix from the left,
+	/// i.e. calculate `other * self`.
+	fn mul_left<L: Indexer>(&self, other: &BoolMat<L, J>) -> BoolMat<L, K>;
+
+	/// Write the map down as dense matrix.
+	fn to_dense(&self) -> BoolMat<J, K>;
+}
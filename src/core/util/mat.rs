@@ -0,0 +1,679 @@
+//! Matrices are at the heart of finite-dimensional linear algebra
+//! and we, too, will use them to perform the calculations we want to do.
+//! While any matrix can be considered to be a vector,
+//! matrices have the additional structure of multiplication
+//! which allows us to elegantly chain linear transformations.
+//! Non-square matrices allow us to change the underlying set
+//! when applying them to vectors.
+//!
+//! The entries may come from any semi-ring.
+//! Over `bool`, which is the case the rules are built on,
+//! see `bool_mat` for the additional structure.
+//! Over `usize`, evaluating an adjacency matrix counts neighbours,
+//! and over `Tropical`, it measures distances.
+//!
+//! # Examples
+//!
+//! ```
+//! # use gorrosion::core::board::{Board, Rect};
+//! # use gorrosion::core::util::bool_vec::BoolVec;
+//! # use gorrosion::core::util::indexer;
+//! # use gorrosion::core::util::mat::Mat;
+//! # use gorrosion::core::util::semiring::Tropical;
+//! let rect = Rect::new(3, 3);
+//! let adj = rect.adjacencies();
+//! // Count the free neighbours of every intersection point,
+//! // here with a single stone in the centre.
+//! // The adjacency is reflexive, so free points count themselves.
+//! let mut free = BoolVec::trues(indexer::Rect::new(3, 3));
+//! free[(1, 1)] = false;
+//! let count = |b| if b { 1 } else { 0 };
+//! let liberties = adj.map(count).eval(&free.map(count));
+//! assert_eq!(liberties[(0, 1)], 3);
+//! assert_eq!(liberties[(1, 1)], 4);
+//! // Measure the distance of every point to the stone.
+//! let step = |b| if b { Tropical::Finite(1) } else { Tropical::Infinite };
+//! let step = &adj.map(step) + &Mat::id_matrix(adj.rows().clone());
+//! let stone = free.map(|b| if b { Tropical::Infinite } else { Tropical::Finite(0) });
+//! let distances = step.eval(&step.eval(&stone));
+//! assert_eq!(distances[(0, 0)], Tropical::Finite(2));
+//! assert_eq!(distances[(1, 2)], Tropical::Finite(1));
+//! ```
+
+use core::util::indexer::{Indexer, Masked, Product, Sum};
+use core::util::semiring::Semiring;
+use core::util::vec::Vec;
+use std::ops::{Add, Index, IndexMut, Mul};
+use std::vec;
+
+// TODO: There is some renaming to be done.
+//       * s/eval/apply/ ?
+
+/// A matrix with values in a semi-ring.
+/// Since not all matrices represent endomorphisms,
+/// rows and columns each have their own `Indexer`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Mat<S: Semiring, J: Indexer, K: Indexer> {
+	rows: J,
+	columns: K,
+	contents: Vec<S, Product<usize, usize>>,
+}
+
+impl<S, J, K> Index<(J::Index, K::Index)> for Mat<S, J, K>
+where
+	S: Semiring,
+	J: Indexer,
+	K: Indexer,
+{
+	type Output = S;
+
+	fn index(&self, (j, k): (J::Index, K::Index)) -> &Self::Output {
+		let i = (self.rows.to_num(j), self.columns.to_num(k));
+		&self.contents[i]
+	}
+}
+
+impl<S, J, K> IndexMut<(J::Index, K::Index)> for Mat<S, J, K>
+where
+	S: Semiring,
+	J: Indexer,
+	K: Indexer,
+{
+	fn index_mut(
+		&mut self,
+		(j, k): (J::Index, K::Index),
+	) -> &mut Self::Output {
+		let i = (self.rows.to_num(j), self.columns.to_num(k));
+		&mut self.contents[i]
+	}
+}
+
+impl<S, J, K> Mat<S, J, K>
+where
+	S: Semiring,
+	J: Indexer,
+	K: Indexer,
+{
+	/// Create a new matrix with all entries zero.
+	pub fn zeros(rows: J, columns: K) -> Self {
+		let rect = Product::new(rows.range(), columns.range());
+		let contents = Vec::zeros(rect);
+		Mat {
+			rows,
+			columns,
+			contents,
+		}
+	}
+
+	/// Wrap the entries, given row by row, into a matrix.
+	pub(crate) fn from_data(
+		rows: J,
+		columns: K,
+		data: vec::Vec<S>,
+	) -> Self {
+		let rect = Product::new(rows.range(), columns.range());
+		let contents = Vec::from_data(data, rect);
+		Mat {
+			rows,
+			columns,
+			contents,
+		}
+	}
+
+	/// Access the entries, row by row, by their internal indices.
+	pub(crate) fn as_slice(&self) -> &[S] {
+		self.contents.as_slice()
+	}
+
+	/// The indexer of the rows.
+	pub fn rows(&self) -> &J {
+		&self.rows
+	}
+
+	/// The indexer of the columns.
+	pub fn columns(&self) -> &K {
+		&self.columns
+	}
+
+	/// Apply a map to every entry,
+	/// e.g. to move from one semi-ring to another.
+	pub fn map<T, F>(&self, op: F) -> Mat<T, J, K>
+	where
+		T: Semiring,
+		F: Fn(S) -> T,
+	{
+		let rows = self.rows.clone();
+		let columns = self.columns.clone();
+		let contents = self.contents.map(op);
+		Mat {
+			rows,
+			columns,
+			contents,
+		}
+	}
+
+	/// Evaluate the matrix on a vector,
+	/// which is considered as a column vector.
+	pub fn eval(&self, v: &Vec<S, K>) -> Vec<S, J> {
+		let matrix = self * Mat::column(v);
+		let indexer = matrix.rows;
+		matrix.contents.reindex(indexer)
+	}
+
+	/// Change the way rows and columns are indexed.
+	pub fn reindex<L, M>(self, rows: L, columns: M) -> Mat<S, L, M>
+	where
+		L: Indexer,
+		M: Indexer,
+	{
+		assert_eq!(self.rows.range(), rows.range());
+		assert_eq!(self.columns.range(), columns.range());
+		let contents = self.contents;
+		Mat {
+			rows,
+			columns,
+			contents,
+		}
+	}
+
+	/// Keep only the rows and columns selected by two masks.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::board::{Board, Rect};
+	/// # use gorrosion::core::util::bool_vec::BoolVec;
+	/// # use gorrosion::core::util::indexer::{self, Indexer, Masked};
+	/// // An L-shaped board, cut out of a square.
+	/// let square = Rect::new(3, 3);
+	/// let mut mask = BoolVec::falses(indexer::Rect::new(3, 3));
+	/// for k in 0..3 {
+	///	mask[(k, 0)] = true;
+	///	mask[(2, k)] = true;
+	/// }
+	/// let l = Masked::new(&mask);
+	/// let adj = square.adjacencies().restrict(l.clone(), l);
+	/// assert_eq!(adj.rows().range(), 5);
+	/// assert!(adj[((1, 0), (2, 0))]);
+	/// ```
+	pub fn restrict(
+		&self,
+		rows: Masked<J>,
+		columns: Masked<K>,
+	) -> Mat<S, Masked<J>, Masked<K>> {
+		assert_eq!(rows.parent(), &self.rows);
+		assert_eq!(columns.parent(), &self.columns);
+		let width = self.columns.range();
+		let ours = self.as_slice();
+		let row_nums = (0..rows.range())
+			.map(|j| self.rows.to_num(rows.to_index(j)));
+		let column_nums: vec::Vec<_> = (0..columns.range())
+			.map(|k| self.columns.to_num(columns.to_index(k)))
+			.collect();
+		let mut data =
+			vec::Vec::with_capacity(rows.range() * columns.range());
+		for j in row_nums {
+			data.extend(column_nums
+				.iter()
+				.map(|&k| ours[j * width + k]));
+		}
+		Mat::from_data(rows, columns, data)
+	}
+
+	/// Swap the roles of rows and columns.
+	pub fn transpose(&self) -> Mat<S, K, J> {
+		let height = self.rows.range();
+		let width = self.columns.range();
+		let ours = self.as_slice();
+		let mut data = vec::Vec::with_capacity(height * width);
+		for k in 0..width {
+			for j in 0..height {
+				data.push(ours[j * width + k]);
+			}
+		}
+		let rows = self.columns.clone();
+		let columns = self.rows.clone();
+		Mat::from_data(rows, columns, data)
+	}
+
+	/// The Kronecker product,
+	/// which is the matrix of the tensor product of linear maps.
+	/// Its entry at `((j, l), (k, m))` is
+	/// the product of `self[(j, k)]` and `other[(l, m)]`.
+	pub fn kronecker<L, M>(
+		&self,
+		other: &Mat<S, L, M>,
+	) -> Mat<S, Product<J, L>, Product<K, M>>
+	where
+		L: Indexer,
+		M: Indexer,
+	{
+		let rows = Product::new(self.rows.clone(), other.rows.clone());
+		let columns = Product::new(
+			self.columns.clone(),
+			other.columns.clone(),
+		);
+		let (height, width) = (self.rows.range(), self.columns.range());
+		let (h, w) = (other.rows.range(), other.columns.range());
+		let ours = self.as_slice();
+		let theirs = other.as_slice();
+		let mut data = vec::Vec::with_capacity(height * width * h * w);
+		for j in 0..height {
+			for l in 0..h {
+				for k in 0..width {
+					let a = ours[j * width + k];
+					let row = &theirs[l * w..(l + 1) * w];
+					data.extend(row
+						.iter()
+						.map(|&b| a.times(b)));
+				}
+			}
+		}
+		Mat::from_data(rows, columns, data)
+	}
+
+	/// Assemble a matrix from four blocks,
+	/// ```none
+	/// ( top_left     top_right    )
+	/// ( bottom_left  bottom_right )
+	/// ```
+	/// indexed by the disjoint unions of their row and column indexers.
+	pub fn from_blocks<L, M>(
+		top_left: &Mat<S, J, K>,
+		top_right: &Mat<S, J, M>,
+		bottom_left: &Mat<S, L, K>,
+		bottom_right: &Mat<S, L, M>,
+	) -> Mat<S, Sum<J, L>, Sum<K, M>>
+	where
+		L: Indexer,
+		M: Indexer,
+	{
+		assert_eq!(top_left.rows, top_right.rows);
+		assert_eq!(bottom_left.rows, bottom_right.rows);
+		assert_eq!(top_left.columns, bottom_left.columns);
+		assert_eq!(top_right.columns, bottom_right.columns);
+		let rows = Sum::new(
+			top_left.rows.clone(),
+			bottom_left.rows.clone(),
+		);
+		let columns = Sum::new(
+			top_left.columns.clone(),
+			top_right.columns.clone(),
+		);
+		let mut data =
+			vec::Vec::with_capacity(rows.range() * columns.range());
+		let width = top_left.columns.range();
+		let w = top_right.columns.range();
+		for j in 0..top_left.rows.range() {
+			data.extend(&top_left.as_slice()
+				[j * width..(j + 1) * width]);
+			data.extend(&top_right.as_slice()[j * w..(j + 1) * w]);
+		}
+		for l in 0..bottom_left.rows.range() {
+			data.extend(&bottom_left.as_slice()
+				[l * width..(l + 1) * width]);
+			data.extend(
+				&bottom_right.as_slice()[l * w..(l + 1) * w]
+			);
+		}
+		Mat::from_data(rows, columns, data)
+	}
+
+	/// Assemble a block-diagonal matrix from two blocks,
+	/// filling the remaining two blocks with zeros.
+	pub fn block_diag<L, M>(
+		&self,
+		other: &Mat<S, L, M>,
+	) -> Mat<S, Sum<J, L>, Sum<K, M>>
+	where
+		L: Indexer,
+		M: Indexer,
+	{
+		let top_right =
+			Mat::zeros(self.rows.clone(), other.columns.clone());
+		let bottom_left =
+			Mat::zeros(other.rows.clone(), self.columns.clone());
+		Mat::from_blocks(self, &top_right, &bottom_left, other)
+	}
+}
+
+impl<S, I> Mat<S, I, ()>
+where
+	S: Semiring,
+	I: Indexer,
+{
+	/// Take a vector and write it as column,
+	/// i.e. a matrix where the columns are indexed by the unit type.
+	fn column(vec: &Vec<S, I>) -> Self {
+		let rows = vec.indexer().clone();
+		let columns = ();
+		let rect = Product::new(rows.range(), 1);
+		let contents = vec.clone().reindex(rect);
+		Mat {
+			rows,
+			columns,
+			contents,
+		}
+	}
+}
+
+impl<S, I> Mat<S, I, I>
+where
+	S: Semiring,
+	I: Indexer,
+{
+	/// Create a diagonal matrix
+	/// whose diagonal entries are given by a vector.
+	pub fn from_diag(diag: &Vec<S, I>) -> Self {
+		let indexer = diag.indexer();
+		let rows = indexer.clone();
+		let columns = indexer.clone();
+		let mut res = Mat::zeros(rows, columns);
+		let len = indexer.range();
+		for i in 0..len {
+			res.contents[(i, i)] = diag[indexer.to_index(i)];
+		}
+		res
+	}
+
+	/// Create the identity matrix to a given `Indexer`.
+	pub fn id_matrix(indexer: I) -> Self {
+		Mat::from_diag(&Vec::ones(indexer))
+	}
+
+	/// The Kronecker sum `self ⊗ id + id ⊗ other`.
+	/// For adjacency matrices of graphs,
+	/// this is the adjacency matrix of their Cartesian product,
+	/// e.g. the product of two paths is a rectangular grid.
+	pub fn kronecker_sum<L>(
+		&self,
+		other: &Mat<S, L, L>,
+	) -> Mat<S, Product<I, L>, Product<I, L>>
+	where
+		L: Indexer,
+	{
+		let ours = self.kronecker(&Mat::id_matrix(other.rows.clone()));
+		let theirs = Mat::id_matrix(self.rows.clone()).kronecker(other);
+		&ours + &theirs
+	}
+
+	/// Multiply the matrix with itself `n` times,
+	/// using repeated squaring.
+	/// The zeroth power is the identity.
+	pub fn pow(&self, mut n: usize) -> Self {
+		let mut res = Mat::id_matrix(self.rows.clone());
+		let mut square = self.clone();
+		while n > 0 {
+			if n % 2 == 1 {
+				res = &res * &square;
+			}
+			n /= 2;
+			if n > 0 {
+				square = &square * &square;
+			}
+		}
+		res
+	}
+}
+
+/// Pointwise addition in the semi-ring.
+impl<S, J, K> Add for &Mat<S, J, K>
+where
+	S: Semiring,
+	J: Indexer,
+	K: Indexer,
+{
+	type Output = Mat<S, J, K>;
+
+	fn add(self, other: Self) -> Self::Output {
+		assert_eq!(self.rows, other.rows);
+		assert_eq!(self.columns, other.columns);
+		let rows = self.rows.clone();
+		let columns = self.columns.clone();
+		let contents = &self.contents + &other.contents;
+		Mat {
+			rows,
+			columns,
+			contents,
+		}
+	}
+}
+
+// TODO: Implement consuming versions.
+impl<'now, S, J, K, L> Mul<&'now Mat<S, K, L>> for &'now Mat<S, J, K>
+where
+	S: Semiring,
+	J: Indexer,
+	K: Indexer,
+	L: Indexer,
+{
+	type Output = Mat<S, J, L>;
+
+	/// Every row of the product is a linear combination
+	/// of the rows of the right factor.
+	fn mul(self, other: &Mat<S, K, L>) -> Self::Output {
+		assert_eq!(self.columns, other.rows);
+		let len = self.columns.range();
+		let rows = self.rows.clone();
+		let columns = other.columns.clone();
+		let width = columns.range();
+		let ours = self.as_slice();
+		let theirs = other.as_slice();
+		let height = rows.range();
+		let mut data = vec![S::zero(); height * width];
+		for j in 0..height {
+			let res = &mut data[j * width..(j + 1) * width];
+			for k in 0..len {
+				let a = ours[j * len + k];
+				if a == S::zero() {
+					continue;
+				}
+				let row = &theirs[k * width..(k + 1) * width];
+				S::add_scaled(res, a, row);
+			}
+		}
+		Mat::from_data(rows, columns, data)
+	}
+}
+
+impl<S, J, K, L> Mul<Mat<S, K, L>> for &Mat<S, J, K>
+where
+	S: Semiring,
+	J: Indexer,
+	K: Indexer,
+	L: Indexer,
+{
+	type Output = Mat<S, J, L>;
+
+	fn mul(self, other: Mat<S, K, L>) -> Self::Output {
+		self * &other
+	}
+}
+
+impl<'now, S, J, K, L> Mul<&'now Mat<S, K, L>> for Mat<S, J, K>
+where
+	S: Semiring,
+	J: Indexer,
+	K: Indexer,
+	L: Indexer,
+{
+	type Output = Mat<S, J, L>;
+
+	fn mul(self, other: &'now Mat<S, K, L>) -> Self::Output {
+		&self * other
+	}
+}
+
+impl<S, J, K, L> Mul<Mat<S, K, L>> for Mat<S, J, K>
+where
+	S: Semiring,
+	J: Indexer,
+	K: Indexer,
+	L: Indexer,
+{
+	type Output = Mat<S, J, L>;
+
+	fn mul(self, other: Mat<S, K, L>) -> Self::Output {
+		&self * &other
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Mat;
+	use core::util::indexer::Indexer;
+	use core::util::semiring::Tropical;
+	use core::util::vec::Vec;
+
+	fn path(n: usize) -> Mat<usize, usize, usize> {
+		let mut adj = Mat::zeros(n, n);
+		for k in 1..n {
+			adj[(k - 1, k)] = 1;
+			adj[(k, k - 1)] = 1;
+		}
+		adj
+	}
+
+	#[test]
+	fn counting() {
+		let adj = path(4);
+		// The number of walks of length two.
+		let walks = &adj * &adj;
+		assert_eq!(walks[(1, 1)], 2);
+		assert_eq!(walks[(0, 2)], 1);
+		assert_eq!(walks[(0, 1)], 0);
+		let degrees = adj.eval(&Vec::ones(4));
+		assert_eq!(degrees, Vec::from_data(vec![1, 2, 2, 1], 4));
+	}
+
+	#[test]
+	fn tropical() {
+		use self::Tropical::{Finite, Infinite};
+		let n = 5;
+		let step = |a| if a == 1 { Finite(1) } else { Infinite };
+		let step = &path(n).map(step) + &Mat::id_matrix(n);
+		let mut distances = step.clone();
+		for _ in 0..n {
+			distances = &distances * &step;
+		}
+		for j in 0..n {
+			for k in 0..n {
+				let d = j.abs_diff(k);
+				assert_eq!(distances[(j, k)], Finite(d));
+			}
+		}
+	}
+
+	#[test]
+	fn powers() {
+		let adj = path(4);
+		assert_eq!(adj.pow(0), Mat::id_matrix(4));
+		assert_eq!(adj.pow(1), adj);
+		assert_eq!(adj.pow(3), &(&adj * &adj) * &adj);
+		// There are 8 walks of length 5 from one end of the path.
+		let walks = adj.pow(5).eval(&Vec::ones(4));
+		assert_eq!(walks[0], 8);
+	}
+
+	#[test]
+	fn transpose() {
+		let mut mat = Mat::zeros(2, 3);
+		mat[(0, 2)] = 4;
+		mat[(1, 0)] = 5;
+		let t = mat.transpose();
+		assert_eq!(t.rows(), &3);
+		assert_eq!(t[(2, 0)], 4);
+		assert_eq!(t[(0, 1)], 5);
+		assert_eq!(t[(0, 0)], 0);
+		assert_eq!(t.transpose(), mat);
+		let adj = path(5);
+		assert_eq!(adj.transpose(), adj);
+	}
+
+	#[test]
+	fn kronecker() {
+		let mut a = Mat::zeros(2, 2);
+		a[(0, 1)] = 2;
+		a[(1, 0)] = 3;
+		let b = path(3);
+		let k = a.kronecker(&b);
+		assert_eq!(k.rows().range(), 6);
+		assert_eq!(k[((0, 0), (1, 1))], 2 * b[(0, 1)]);
+		assert_eq!(k[((1, 2), (0, 1))], 3);
+		assert_eq!(k[((0, 2), (1, 1))], 2);
+		assert_eq!(k[((0, 2), (0, 1))], 0);
+		// The mixed product property.
+		let c = Mat::id_matrix(2);
+		let d = path(3);
+		assert_eq!(
+			&k * &c.kronecker(&d),
+			(&a * &c).kronecker(&(&b * &d))
+		);
+	}
+
+	#[test]
+	fn kronecker_sum() {
+		// The product of two paths is a grid.
+		let grid = path(2).kronecker_sum(&path(3));
+		let degrees = grid.eval(&Vec::ones(grid.rows().clone()));
+		assert_eq!(degrees[(0, 0)], 2);
+		assert_eq!(degrees[(1, 1)], 3);
+		assert_eq!(grid[((0, 1), (1, 1))], 1);
+		assert_eq!(grid[((0, 1), (1, 2))], 0);
+	}
+
+	#[test]
+	fn blocks() {
+		use core::util::indexer::Either::{Left, Right};
+		let a = path(2);
+		let b = path(3);
+		let mut c = Mat::zeros(2, 3);
+		c[(1, 0)] = 7;
+		let d = c.transpose();
+		let mat = Mat::from_blocks(&a, &c, &d, &b);
+		assert_eq!(mat.rows().range(), 5);
+		assert_eq!(mat[(Left(0), Left(1))], 1);
+		assert_eq!(mat[(Left(1), Right(0))], 7);
+		assert_eq!(mat[(Right(0), Left(1))], 7);
+		assert_eq!(mat[(Right(2), Right(1))], 1);
+		assert_eq!(mat[(Right(2), Left(1))], 0);
+		let diag = a.block_diag(&b);
+		assert_eq!(diag[(Left(0), Left(1))], 1);
+		assert_eq!(diag[(Left(1), Right(0))], 0);
+		assert_eq!(diag[(Right(1), Right(2))], 1);
+	}
+
+	#[test]
+	fn restrict() {
+		use core::util::indexer::Masked;
+		let mut mask = Vec::ones(5);
+		mask[1] = false;
+		mask[3] = false;
+		let odd = Masked::new(&!&mask);
+		let even = Masked::new(&mask);
+		let adj = path(5);
+		let between = adj.restrict(odd.clone(), even.clone());
+		assert_eq!(between.rows().range(), 2);
+		assert_eq!(between.columns().range(), 3);
+		assert_eq!(between[(1, 0)], 1);
+		assert_eq!(between[(3, 4)], 1);
+		assert_eq!(between[(1, 4)], 0);
+		// There are no edges between even vertices of a path.
+		let within = adj.restrict(even.clone(), even);
+		assert_eq!(
+			within,
+			Mat::zeros(
+				within.rows().clone(),
+				within.columns().clone()
+			)
+		);
+	}
+
+	#[test]
+	fn identity() {
+		let adj = path(3);
+		let id = Mat::id_matrix(3);
+		assert_eq!(&adj * &id, adj);
+		assert_eq!(&id * &adj, adj);
+	}
+}
@@ -1,3 +1,10 @@
 pub mod bool_mat;
 pub mod bool_vec;
 pub mod indexer;
+mod kernels;
+pub mod linear_map;
+pub mod mat;
+pub mod permutation;
+pub mod semiring;
+pub mod sparse_mat;
+pub mod vec;
@@ -0,0 +1,215 @@
+//! Permutations of the index set of an `Indexer`,
+//! such as the symmetries of a board.
+//! They act on vectors by moving entries around
+//! and on matrices by moving rows and columns alike,
+//! so positions and everything derived from them
+//! can be carried along any symmetry.
+
+use core::util::indexer::Indexer;
+use core::util::mat::Mat;
+use core::util::semiring::Semiring;
+use core::util::vec::Vec;
+use std::vec;
+
+/// A bijection of the index set of an indexer onto itself.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Permutation<I: Indexer> {
+	indexer: I,
+	images: vec::Vec<usize>,
+}
+
+impl<I> Permutation<I>
+where
+	I: Indexer,
+{
+	/// The permutation leaving everything in place.
+	pub fn identity(indexer: I) -> Self {
+		let images = (0..indexer.range()).collect();
+		Permutation { indexer, images }
+	}
+
+	/// Tabulate a map from the index set to itself.
+	/// Returns `None` if it is not a bijection.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::util::permutation::Permutation;
+	/// let shift = Permutation::from_fn(5, |i| (i + 1) % 5).unwrap();
+	/// assert_eq!(shift.image(4), 0);
+	/// assert_eq!(shift.inverse().image(0), 4);
+	/// assert!(Permutation::from_fn(5, |i| i / 2).is_none());
+	/// ```
+	pub fn from_fn<F>(indexer: I, f: F) -> Option<Self>
+	where
+		F: Fn(I::Index) -> I::Index,
+	{
+		let size = indexer.range();
+		let mut hit = vec![false; size];
+		let mut images = vec::Vec::with_capacity(size);
+		for n in 0..size {
+			let image = f(indexer.to_index(n));
+			if !indexer.is_valid(image) {
+				return None;
+			}
+			let m = indexer.to_num(image);
+			if hit[m] {
+				return None;
+			}
+			hit[m] = true;
+			images.push(m);
+		}
+		Some(Permutation { indexer, images })
+	}
+
+	/// Wrap the internal indices of the images of `0..range`,
+	/// which have to form a bijection.
+	pub(crate) fn from_images(indexer: I, images: vec::Vec<usize>) -> Self {
+		debug_assert_eq!(images.len(), indexer.range());
+		Permutation { indexer, images }
+	}
+
+	/// Get a reference to the indexer.
+	pub fn indexer(&self) -> &I {
+		&self.indexer
+	}
+
+	/// Where the permutation sends an index.
+	pub fn image(&self, i: I::Index) -> I::Index {
+		let n = self.images[self.indexer.to_num(i)];
+		self.indexer.to_index(n)
+	}
+
+	/// Whether the permutation leaves everything in place.
+	pub fn is_identity(&self) -> bool {
+		self.images.iter().enumerate().all(|(n, &m)| n == m)
+	}
+
+	/// The permutation undoing this one.
+	pub fn inverse(&self) -> Self {
+		let mut images = vec![0; self.images.len()];
+		for (n, &m) in self.images.iter().enumerate() {
+			images[m] = n;
+		}
+		let indexer = self.indexer.clone();
+		Permutation { indexer, images }
+	}
+
+	/// First apply `other`, then `self`.
+	pub fn compose(&self, other: &Self) -> Self {
+		assert_eq!(self.indexer, other.indexer);
+		let images =
+			other.images.iter().map(|&m| self.images[m]).collect();
+		let indexer = self.indexer.clone();
+		Permutation { indexer, images }
+	}
+
+	/// Move every entry of a vector from its index to the image thereof,
+	/// e.g. to carry the stones of a position along a symmetry.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::util::bool_vec::BoolVec;
+	/// # use gorrosion::core::util::indexer;
+	/// # use gorrosion::core::util::permutation::Permutation;
+	/// let rect = indexer::Rect::new(2, 3);
+	/// let mirror = Permutation::from_fn(rect.clone(), |(j, k)| (j, 2 - k));
+	/// let mirror = mirror.unwrap();
+	/// let mut stones = BoolVec::falses(rect);
+	/// stones[(1, 0)] = true;
+	/// let mirrored = mirror.permute(&stones);
+	/// assert!(mirrored[(1, 2)]);
+	/// assert!(!mirrored[(1, 0)]);
+	/// ```
+	pub fn permute<S>(&self, v: &Vec<S, I>) -> Vec<S, I>
+	where
+		S: Semiring,
+	{
+		assert_eq!(&self.indexer, v.indexer());
+		let mut data = vec![S::zero(); self.images.len()];
+		for (&m, &s) in self.images.iter().zip(v.as_slice()) {
+			data[m] = s;
+		}
+		Vec::from_data(data, self.indexer.clone())
+	}
+
+	/// Move rows and columns of a matrix alike,
+	/// so that the entry at `(j, k)` ends up
+	/// at the images of `j` and `k`.
+	/// Symmetries of a board leave its adjacency matrix unchanged.
+	pub fn conjugate<S>(&self, m: &Mat<S, I, I>) -> Mat<S, I, I>
+	where
+		S: Semiring,
+	{
+		assert_eq!(&self.indexer, m.rows());
+		assert_eq!(&self.indexer, m.columns());
+		let size = self.images.len();
+		let ours = m.as_slice();
+		let mut data = vec![S::zero(); size * size];
+		for (j, &a) in self.images.iter().enumerate() {
+			for (k, &b) in self.images.iter().enumerate() {
+				data[a * size + b] = ours[j * size + k];
+			}
+		}
+		let indexer = self.indexer.clone();
+		Mat::from_data(indexer.clone(), indexer, data)
+	}
+}
+
+// TODO: Throw out as soon as coverage tools can handle doctests.
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::util::bool_mat::BoolMat;
+	use core::util::bool_vec::BoolVec;
+	use core::util::indexer;
+	use core::util::linear_map::LinearMap;
+
+	#[test]
+	fn doctests() {
+		let shift = Permutation::from_fn(5, |i| (i + 1) % 5).unwrap();
+		assert_eq!(shift.image(4), 0);
+		assert_eq!(shift.inverse().image(0), 4);
+		assert!(Permutation::from_fn(5, |i| i / 2).is_none());
+
+		let rect = indexer::Rect::new(2, 3);
+		let mirror =
+			Permutation::from_fn(rect.clone(), |(j, k)| (j, 2 - k));
+		let mirror = mirror.unwrap();
+		let mut stones = BoolVec::falses(rect);
+		stones[(1, 0)] = true;
+		let mirrored = mirror.permute(&stones);
+		assert!(mirrored[(1, 2)]);
+		assert!(!mirrored[(1, 0)]);
+	}
+
+	#[test]
+	fn group_laws() {
+		let shift = Permutation::from_fn(5, |i| (i + 1) % 5).unwrap();
+		let flip = Permutation::from_fn(5, |i| 4 - i).unwrap();
+		let id = Permutation::identity(5);
+		assert!(id.is_identity());
+		assert!(!shift.is_identity());
+		assert!(shift.compose(&shift.inverse()).is_identity());
+		assert_eq!(shift.compose(&id), shift);
+		// Composition applies the right factor first.
+		assert_eq!(flip.compose(&shift).image(0), 3);
+		assert_eq!(shift.compose(&flip).image(0), 0);
+		// Out of range images are no bijection.
+		assert!(Permutation::from_fn(5, |i| i + 1).is_none());
+	}
+
+	#[test]
+	fn action() {
+		let shift = Permutation::from_fn(4, |i| (i + 1) % 4).unwrap();
+		let v = Vec::from_data(vec![1, 2, 3, 4_usize], 4);
+		assert_eq!(shift.permute(&v).as_slice(), &[4, 1, 2, 3]);
+		assert_eq!(shift.inverse().permute(&shift.permute(&v)), v);
+		let mut m = BoolMat::falses(4, 4);
+		m[(0, 1)] = true;
+		let m = shift.conjugate(&m);
+		assert!(m[(1, 2)]);
+		assert_eq!(m.entries(), vec![(1, 2)]);
+	}
+}
@@ -0,0 +1,144 @@
+//! A semi-ring is a ring that may lack additive inverses.
+//! This is all the structure linear algebra needs
+//! to multiply matrices and evaluate them on vectors,
+//! and there are plenty of useful examples:
+//! Boolean logic answers reachability questions,
+//! the natural numbers count,
+//! and the tropical numbers measure distances.
+
+use core::util::kernels;
+use std::fmt::Debug;
+
+/// The operations making a type into a (commutative) semi-ring.
+pub trait Semiring: Copy + Eq + Debug {
+	/// The neutral element of addition.
+	fn zero() -> Self;
+
+	/// The neutral element of multiplication.
+	fn one() -> Self;
+
+	/// Addition, which is associative and commutative.
+	fn plus(self, other: Self) -> Self;
+
+	/// Multiplication, which is associative
+	/// and distributes over addition.
+	fn times(self, other: Self) -> Self;
+
+	/// Add `factor` times `row` to `acc`, entry by entry.
+	/// This is the inner loop of matrix multiplication,
+	/// so semi-rings may provide a faster implementation.
+	fn add_scaled(acc: &mut [Self], factor: Self, row: &[Self]) {
+		for (a, &b) in acc.iter_mut().zip(row) {
+			*a = a.plus(factor.times(b));
+		}
+	}
+}
+
+/// The two-element semi-ring with “or” as addition
+/// and “and” as multiplication.
+impl Semiring for bool {
+	fn zero() -> Self {
+		false
+	}
+
+	fn one() -> Self {
+		true
+	}
+
+	fn plus(self, other: Self) -> Self {
+		self | other
+	}
+
+	fn times(self, other: Self) -> Self {
+		self & other
+	}
+
+	fn add_scaled(acc: &mut [Self], factor: Self, row: &[Self]) {
+		if factor {
+			kernels::or_assign(acc, row);
+		}
+	}
+}
+
+/// The counting semi-ring of the natural numbers.
+impl Semiring for usize {
+	fn zero() -> Self {
+		0
+	}
+
+	fn one() -> Self {
+		1
+	}
+
+	fn plus(self, other: Self) -> Self {
+		self + other
+	}
+
+	fn times(self, other: Self) -> Self {
+		self * other
+	}
+}
+
+/// The tropical semi-ring of the natural numbers extended by infinity,
+/// with the minimum as addition and addition as multiplication.
+/// Matrix multiplication over it finds shortest paths.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Tropical {
+	Finite(usize),
+	Infinite,
+}
+
+impl Semiring for Tropical {
+	fn zero() -> Self {
+		Tropical::Infinite
+	}
+
+	fn one() -> Self {
+		Tropical::Finite(0)
+	}
+
+	fn plus(self, other: Self) -> Self {
+		self.min(other)
+	}
+
+	fn times(self, other: Self) -> Self {
+		match (self, other) {
+			(Tropical::Finite(a), Tropical::Finite(b)) => {
+				Tropical::Finite(a + b)
+			}
+			_ => Tropical::Infinite,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Semiring, Tropical};
+
+	#[test]
+	fn boolean() {
+		assert!(!bool::zero());
+		assert!(bool::one());
+		assert!(true.plus(false));
+		assert!(!true.times(false));
+	}
+
+	#[test]
+	fn counting() {
+		assert_eq!(usize::zero(), 0);
+		assert_eq!(usize::one(), 1);
+		assert_eq!(3.plus(4), 7);
+		assert_eq!(3.times(4), 12);
+	}
+
+	#[test]
+	fn tropical() {
+		use self::Tropical::{Finite, Infinite};
+		assert_eq!(Tropical::zero(), Infinite);
+		assert_eq!(Tropical::one(), Finite(0));
+		assert_eq!(Finite(3).plus(Finite(4)), Finite(3));
+		assert_eq!(Finite(3).plus(Infinite), Finite(3));
+		assert_eq!(Finite(3).times(Finite(4)), Finite(7));
+		assert_eq!(Finite(3).times(Infinite), Infinite);
+	}
+}
@@ -0,0 +1,308 @@
+//! Most relations we care about are sparse:
+//! On a graph board, every vertex only has a handful of neighbours,
+//! no matter how large the graph gets.
+//! Storing all the unset entries of such a matrix
+//! wastes both memory and time,
+//! so here we only remember the set ones, row by row.
+
+use core::util::bool_mat::BoolMat;
+use core::util::bool_vec::BoolVec;
+use core::util::indexer::Indexer;
+use core::util::kernels;
+use core::util::linear_map::LinearMap;
+use std::ops::Index;
+
+/// A Boolean matrix in compressed sparse row format.
+/// The set entries of row `j` are the columns
+/// `columns_of[row_starts[j]..row_starts[j + 1]]`,
+/// listed in ascending order and without repetition.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SparseBoolMat<J: Indexer, K: Indexer> {
+	rows: J,
+	columns: K,
+	row_starts: Vec<usize>,
+	columns_of: Vec<usize>,
+}
+
+impl<J, K> SparseBoolMat<J, K>
+where
+	J: Indexer,
+	K: Indexer,
+{
+	/// Create a new sparse matrix with all entries unset.
+	pub fn falses(rows: J, columns: K) -> Self {
+		let row_starts = vec![0; rows.range() + 1];
+		let columns_of = Vec::new();
+		SparseBoolMat {
+			rows,
+			columns,
+			row_starts,
+			columns_of,
+		}
+	}
+
+	/// Compress a dense matrix.
+	pub fn from_dense(dense: &BoolMat<J, K>) -> Self {
+		let rows = dense.rows().clone();
+		let columns = dense.columns().clone();
+		let width = columns.range();
+		let data = dense.as_slice();
+		let mut row_starts = Vec::with_capacity(rows.range() + 1);
+		let mut columns_of = Vec::new();
+		row_starts.push(0);
+		for j in 0..rows.range() {
+			let row = &data[j * width..(j + 1) * width];
+			columns_of.extend((0..width).filter(|&k| row[k]));
+			row_starts.push(columns_of.len());
+		}
+		SparseBoolMat {
+			rows,
+			columns,
+			row_starts,
+			columns_of,
+		}
+	}
+
+	/// The number of set entries.
+	pub fn count(&self) -> usize {
+		self.columns_of.len()
+	}
+
+	/// The set entries of a row as internal column indices.
+	fn row(&self, j: usize) -> &[usize] {
+		let start = self.row_starts[j];
+		let end = self.row_starts[j + 1];
+		&self.columns_of[start..end]
+	}
+
+	/// Check whether an entry, given by internal indices, is set.
+	fn contains(&self, j: usize, k: usize) -> bool {
+		self.row(j).binary_search(&k).is_ok()
+	}
+}
+
+impl<J, K> Index<(J::Index, K::Index)> for SparseBoolMat<J, K>
+where
+	J: Indexer,
+	K: Indexer,
+{
+	type Output = bool;
+
+	fn index(&self, (j, k): (J::Index, K::Index)) -> &Self::Output {
+		if self.entry(j, k) {
+			&true
+		} else {
+			&false
+		}
+	}
+}
+
+impl<J, K> LinearMap<J, K> for SparseBoolMat<J, K>
+where
+	J: Indexer,
+	K: Indexer,
+{
+	fn from_entries<E>(rows: J, columns: K, entries: E) -> Self
+	where
+		E: IntoIterator<Item = (J::Index, K::Index)>,
+	{
+		let mut lists = vec![Vec::new(); rows.range()];
+		for (j, k) in entries {
+			lists[rows.to_num(j)].push(columns.to_num(k));
+		}
+		let mut row_starts = Vec::with_capacity(rows.range() + 1);
+		let mut columns_of = Vec::new();
+		row_starts.push(0);
+		for mut list in lists {
+			list.sort_unstable();
+			list.dedup();
+			columns_of.append(&mut list);
+			row_starts.push(columns_of.len());
+		}
+		SparseBoolMat {
+			rows,
+			columns,
+			row_starts,
+			columns_of,
+		}
+	}
+
+	fn rows(&self) -> &J {
+		&self.rows
+	}
+
+	fn columns(&self) -> &K {
+		&self.columns
+	}
+
+	fn entry(&self, j: J::Index, k: K::Index) -> bool {
+		self.contains(self.rows.to_num(j), self.columns.to_num(k))
+	}
+
+	fn entries(&self) -> Vec<(J::Index, K::Index)> {
+		let mut res = Vec::with_capacity(self.count());
+		for j in 0..self.rows.range() {
+			let row = self.rows.to_index(j);
+			for &k in self.row(j) {
+				res.push((row, self.columns.to_index(k)));
+			}
+		}
+		res
+	}
+
+	fn eval(&self, v: &BoolVec<K>) -> BoolVec<J> {
+		assert_eq!(&self.columns, v.indexer());
+		let v = v.as_slice();
+		let data = (0..self.rows.range())
+			.map(|j| self.row(j).iter().any(|&k| v[k]))
+			.collect();
+		BoolVec::from_data(data, self.rows.clone())
+	}
+
+	fn mul_right<L: Indexer>(
+		&self,
+		other: &BoolMat<K, L>,
+	) -> BoolMat<J, L> {
+		assert_eq!(&self.columns, other.rows());
+		let width = other.columns().range();
+		let theirs = other.as_slice();
+		let mut data = vec![false; self.rows.range() * width];
+		for j in 0..self.rows.range() {
+			let res = &mut data[j * width..(j + 1) * width];
+			for &k in self.row(j) {
+				let row = &theirs[k * width..(k + 1) * width];
+				kernels::or_assign(res, row);
+			}
+		}
+		let rows = self.rows.clone();
+		let columns = other.columns().clone();
+		BoolMat::from_data(rows, columns, data)
+	}
+
+	fn mul_left<L: Indexer>(&self, other: &BoolMat<L, J>) -> BoolMat<L, K> {
+		assert_eq!(other.columns(), &self.rows);
+		let len = self.rows.range();
+		let width = self.columns.range();
+		let theirs = other.as_slice();
+		let mut data = vec![false; other.rows().range() * width];
+		for l in 0..other.rows().range() {
+			let res = &mut data[l * width..(l + 1) * width];
+			let row = &theirs[l * len..(l + 1) * len];
+			for j in (0..len).filter(|&j| row[j]) {
+				for &k in self.row(j) {
+					res[k] = true;
+				}
+			}
+		}
+		let rows = other.rows().clone();
+		let columns = self.columns.clone();
+		BoolMat::from_data(rows, columns, data)
+	}
+
+	fn to_dense(&self) -> BoolMat<J, K> {
+		let height = self.rows.range();
+		let width = self.columns.range();
+		let mut data = vec![false; height * width];
+		for j in 0..height {
+			for &k in self.row(j) {
+				data[j * width + k] = true;
+			}
+		}
+		let rows = self.rows.clone();
+		let columns = self.columns.clone();
+		BoolMat::from_data(rows, columns, data)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::SparseBoolMat;
+	use core::board::{Board, Rect};
+	use core::util::bool_mat::BoolMat;
+	use core::util::bool_vec::BoolVec;
+	use core::util::indexer;
+	use core::util::linear_map::LinearMap;
+
+	fn path(n: usize) -> SparseBoolMat<usize, usize> {
+		let edges = (1..n).flat_map(|k| vec![(k - 1, k), (k, k - 1)]);
+		SparseBoolMat::from_entries(n, n, edges)
+	}
+
+	#[test]
+	fn falses() {
+		let sparse = SparseBoolMat::falses(3, 4);
+		assert_eq!(sparse.count(), 0);
+		assert_eq!(sparse.to_dense(), BoolMat::falses(3, 4));
+	}
+
+	#[test]
+	fn entries() {
+		let sparse = path(5);
+		assert_eq!(sparse.count(), 8);
+		assert!(sparse[(2, 3)]);
+		assert!(sparse.entry(3, 2));
+		assert!(!sparse[(2, 2)]);
+		assert!(!sparse.entry(0, 4));
+	}
+
+	#[test]
+	fn list_entries() {
+		let sparse = path(3);
+		let entries = vec![(0, 1), (1, 0), (1, 2), (2, 1)];
+		assert_eq!(sparse.entries(), entries);
+		assert_eq!(sparse.to_dense().entries(), entries);
+	}
+
+	#[test]
+	fn duplicate_entries() {
+		let entries = vec![(0, 1), (0, 1), (1, 0)];
+		let sparse = SparseBoolMat::from_entries(2, 2, entries);
+		assert_eq!(sparse.count(), 2);
+	}
+
+	#[test]
+	fn dense_round_trip() {
+		let rect = Rect::new(4, 5);
+		let dense = rect.adjacencies();
+		let sparse = SparseBoolMat::from_dense(dense);
+		// Every point is adjacent to itself and its neighbours.
+		assert_eq!(sparse.count(), 20 + 2 * (4 * 4 + 3 * 5));
+		assert_eq!(&sparse.to_dense(), dense);
+	}
+
+	#[test]
+	fn eval() {
+		let sparse = path(4);
+		let mut v = BoolVec::falses(4);
+		v[0] = true;
+		let w = BoolVec::from_data(vec![false, true, false, false], 4);
+		assert_eq!(sparse.eval(&v), w);
+		assert_eq!(sparse.eval(&v), sparse.to_dense().eval(&v));
+	}
+
+	#[test]
+	fn multiplication() {
+		let rect = Rect::new(3, 4);
+		let dense = rect.adjacencies();
+		let sparse = SparseBoolMat::from_dense(dense);
+		let square = dense * dense;
+		assert_eq!(sparse.mul_right(dense), square);
+		assert_eq!(sparse.mul_left(dense), square);
+		let diag = BoolMat::from_diag(&BoolVec::from_data(
+			(0..12).map(|n| n % 3 == 0).collect(),
+			indexer::Rect::new(3, 4),
+		));
+		assert_eq!(sparse.mul_left(&diag), &diag * dense);
+		assert_eq!(sparse.mul_right(&diag), dense * &diag);
+	}
+
+	#[test]
+	fn multiplication_without_columns() {
+		let sparse = path(3);
+		let empty = BoolMat::falses(3, 0);
+		assert_eq!(sparse.mul_right(&empty), empty);
+		let sparse = SparseBoolMat::falses(3, 0);
+		let dense = BoolMat::trues(2, 3);
+		assert_eq!(sparse.mul_left(&dense), BoolMat::falses(2, 0));
+	}
+}
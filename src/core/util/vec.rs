@@ -0,0 +1,191 @@
+//! Vectors with entries in a semi-ring are functions
+//! from a finite index set into that semi-ring.
+//! Together with matrices, they allow many computations
+//! to be implemented as simple linear algebra.
+//! For Boolean entries, see `bool_vec`,
+//! which treats them as characteristic functions of sets.
+
+use core::util::indexer::{Indexer, Sum};
+use core::util::semiring::Semiring;
+use std::ops::{Add, Index, IndexMut};
+use std::vec;
+
+/// A vector with values in a semi-ring.
+/// For convenience and a poor emulation of type-checking
+/// we do not index these over integers directly
+/// but use an `Indexer`.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Vec<S: Semiring, I: Indexer> {
+	data: vec::Vec<S>,
+	indexer: I,
+}
+
+impl<S: Semiring, I: Indexer> Index<I::Index> for Vec<S, I> {
+	type Output = S;
+
+	fn index(&self, i: I::Index) -> &Self::Output {
+		&self.data[self.indexer.to_num(i)]
+	}
+}
+
+impl<S: Semiring, I: Indexer> IndexMut<I::Index> for Vec<S, I> {
+	fn index_mut(&mut self, i: I::Index) -> &mut Self::Output {
+		&mut self.data[self.indexer.to_num(i)]
+	}
+}
+
+impl<S, I> Vec<S, I>
+where
+	S: Semiring,
+	I: Indexer,
+{
+	/// Wrap an existing std::vec::Vec into a vector.
+	pub fn from_data(data: vec::Vec<S>, indexer: I) -> Self {
+		assert_eq!(data.len(), indexer.range());
+		Vec { data, indexer }
+	}
+
+	/// Create a new vector with all entries being zero.
+	pub fn zeros(indexer: I) -> Self {
+		let size = indexer.range();
+		let data = vec![S::zero(); size];
+		Vec { data, indexer }
+	}
+
+	/// Create a new vector with all entries being one.
+	pub fn ones(indexer: I) -> Self {
+		let size = indexer.range();
+		let data = vec![S::one(); size];
+		Vec { data, indexer }
+	}
+
+	/// Apply a map to every entry,
+	/// e.g. to move from one semi-ring to another.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::util::bool_vec::BoolVec;
+	/// let a = BoolVec::from_data(vec![true, false, true], 3);
+	/// let b = a.map(|b| if b { 5 } else { 0 });
+	/// assert_eq!(b[2], 5);
+	/// ```
+	pub fn map<T, F>(&self, op: F) -> Vec<T, I>
+	where
+		T: Semiring,
+		F: Fn(S) -> T,
+	{
+		let indexer = self.indexer.clone();
+		let data = self.data.iter().map(|&s| op(s)).collect();
+		Vec { data, indexer }
+	}
+
+	/// Consider two vectors as functions on the same set
+	/// and combine them pointwise.
+	// TODO: Implement consuming version.
+	pub(crate) fn zip_with<F>(&self, other: &Self, op: F) -> Self
+	where
+		F: Fn(S, S) -> S,
+	{
+		assert_eq!(self.indexer, other.indexer);
+		let indexer = self.indexer.clone();
+		let data = self
+			.data
+			.iter()
+			.zip(&other.data)
+			.map(|(&a, &b)| op(a, b))
+			.collect();
+		Vec { data, indexer }
+	}
+
+	/// Change the way the vector is indexed.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::util::bool_vec::BoolVec;
+	/// # use gorrosion::core::util::indexer;
+	/// let rect = indexer::Rect::new(2, 3);
+	/// let num = 6;
+	/// let vec_rect = BoolVec::falses(rect);
+	/// let vec_num = BoolVec::falses(num);
+	/// assert_eq!(vec_num, vec_rect.reindex(num));
+	/// ```
+	pub fn reindex<J>(self, indexer: J) -> Vec<S, J>
+	where
+		J: Indexer,
+	{
+		assert_eq!(self.indexer.range(), indexer.range());
+		let data = self.data;
+		Vec { indexer, data }
+	}
+
+	/// Get a reference to the indexer.
+	pub fn indexer(&self) -> &I {
+		&self.indexer
+	}
+
+	/// Access the entries by their internal indices.
+	pub(crate) fn as_slice(&self) -> &[S] {
+		&self.data
+	}
+
+	/// Mutably access the entries by their internal indices.
+	pub(crate) fn as_mut_slice(&mut self) -> &mut [S] {
+		&mut self.data
+	}
+}
+
+impl<S, A, B> Vec<S, Sum<A, B>>
+where
+	S: Semiring,
+	A: Indexer,
+	B: Indexer,
+{
+	/// Concatenate two vectors
+	/// to a vector on the disjoint union of their index sets.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use gorrosion::core::util::bool_vec::BoolVec;
+	/// # use gorrosion::core::util::indexer::Either;
+	/// # use gorrosion::core::util::vec::Vec;
+	/// let a = BoolVec::trues(2);
+	/// let b = BoolVec::falses(3);
+	/// let sum = Vec::direct_sum(&a, &b);
+	/// assert!(sum[Either::Left(1)]);
+	/// assert!(!sum[Either::Right(0)]);
+	/// assert_eq!(sum.split(), (a, b));
+	/// ```
+	pub fn direct_sum(left: &Vec<S, A>, right: &Vec<S, B>) -> Self {
+		let indexer =
+			Sum::new(left.indexer.clone(), right.indexer.clone());
+		let mut data = left.data.clone();
+		data.extend(&right.data);
+		Vec { data, indexer }
+	}
+
+	/// Split a vector on a disjoint union into its two parts.
+	pub fn split(&self) -> (Vec<S, A>, Vec<S, B>) {
+		let left = self.indexer.left().clone();
+		let right = self.indexer.right().clone();
+		let (l, r) = self.data.split_at(left.range());
+		let left = Vec::from_data(l.to_vec(), left);
+		let right = Vec::from_data(r.to_vec(), right);
+		(left, right)
+	}
+}
+
+/// Pointwise addition in the semi-ring.
+impl<S, I> Add for &Vec<S, I>
+where
+	S: Semiring,
+	I: Indexer,
+{
+	type Output = Vec<S, I>;
+
+	fn add(self, other: Self) -> Self::Output {
+		self.zip_with(other, Semiring::plus)
+	}
+}
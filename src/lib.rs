@@ -1 +1,5 @@
+// Examples in the docs are indented with tabs like the rest of the code,
+// see rustfmt.toml.
+#![allow(clippy::tabs_in_doc_comments)]
+
 pub mod core;
@@ -0,0 +1,84 @@
+#!/usr/bin/env python3
+"""Count move sequences of Go on small square boards.
+
+An implementation independent of the Rust code,
+sharing nothing but the conventions:
+positional superko against every earlier position,
+no suicide, passes count as moves, resignations do not,
+and no move follows two passes in a row.
+Groups and liberties are found by flood fill on a plain grid.
+
+Usage: tools/perft.py SIZE DEPTH
+"""
+
+import sys
+
+
+def neighbours(size, p):
+	r, c = divmod(p, size)
+	for dr, dc in ((-1, 0), (1, 0), (0, -1), (0, 1)):
+		rr, cc = r + dr, c + dc
+		if 0 <= rr < size and 0 <= cc < size:
+			yield rr * size + cc
+
+
+def group(size, board, p):
+	"""The stones connected to p and whether they have a liberty."""
+	colour = board[p]
+	seen = {p}
+	todo = [p]
+	free = False
+	while todo:
+		q = todo.pop()
+		for n in neighbours(size, q):
+			if board[n] is None:
+				free = True
+			elif board[n] == colour and n not in seen:
+				seen.add(n)
+				todo.append(n)
+	return seen, free
+
+
+def place(size, board, p, colour):
+	"""The position after a placement, or None for suicide."""
+	board = list(board)
+	board[p] = colour
+	for n in neighbours(size, p):
+		if board[n] is not None and board[n] != colour:
+			stones, free = group(size, board, n)
+			if not free:
+				for s in stones:
+					board[s] = None
+	_, free = group(size, board, p)
+	if not free:
+		return None
+	return tuple(board)
+
+
+def perft(size, board, colour, history, passes, depth):
+	if depth == 0:
+		return 1
+	if passes == 2:
+		return 0
+	total = perft(size, board, 1 - colour, history, passes + 1, depth - 1)
+	for p in range(size * size):
+		if board[p] is not None:
+			continue
+		after = place(size, board, p, colour)
+		if after is None or after in history:
+			continue
+		history.add(after)
+		total += perft(size, after, 1 - colour, history, 0, depth - 1)
+		history.remove(after)
+	return total
+
+
+def main():
+	size, depth = int(sys.argv[1]), int(sys.argv[2])
+	empty = (None,) * (size * size)
+	for d in range(depth + 1):
+		print(d, perft(size, empty, 0, {empty}, 0, d))
+
+
+if __name__ == "__main__":
+	main()